@@ -0,0 +1,67 @@
+/*
+ * indent-stack
+ *
+ * Copyright (C) 2019 chankyin
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Compares Fermat inversion with the extended Euclidean algorithm for prime moduli on either
+//! side of `FERMAT_INV_MAX_COST`.
+//!
+//! Run with `cargo bench --bench inv`. The cost printed for each modulus is the number of
+//! multiplications in `self^(MOD - 2)`, which is what `inv` compares against the threshold.
+
+extern crate alloc;
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use generic_modular::{def_modular, Modular, WideModular};
+
+def_modular!(Mod251 : u16 | i16, 251 ; mod251_lbl);
+def_modular!(Mod65521 : u32 | i32, 65521 ; mod65521_lbl);
+def_modular!(Mod16777213 : u64 | i64, 16_777_213 ; mod16777213_lbl);
+def_modular!(Mod998244353 : u64 | i64, 998_244_353 ; mod998244353_lbl);
+def_modular!(Mod1073741789 : u64 | i64, 1_073_741_789 ; mod1073741789_lbl);
+
+const ROUNDS: u32 = 200_000;
+
+fn time(mut f: impl FnMut(u32) -> u128) -> f64 {
+    let start = Instant::now();
+    let mut acc = 0;
+    for i in 1..=ROUNDS {
+        acc ^= f(i);
+    }
+    black_box(acc);
+    start.elapsed().as_nanos() as f64 / f64::from(ROUNDS)
+}
+
+fn bench<M, T>(name: &str)
+where M: Modular<T> + WideModular, T: Copy + std::fmt::Debug + Default + std::ops::Add + std::ops::Sub + std::ops::Mul + std::ops::Rem {
+    let exp = M::MOD_U128 - 2;
+    let cost = 128 - exp.leading_zeros() + exp.count_ones();
+    let value = |i: u32| M::from_u128_reduced(u128::from(i) % (M::MOD_U128 - 1) + 1);
+    let fermat = time(|i| black_box(value(i)).prime_inv().unwrap().remainder_u128());
+    let egcd = time(|i| black_box(value(i)).coprime_inv().unwrap().remainder_u128());
+    println!("{:>12} cost {:>3}: fermat {:>7.1} ns, egcd {:>7.1} ns, inv uses {:?}",
+             name, cost, fermat, egcd, M::inverse_strategy_for_modulus());
+}
+
+fn main() {
+    bench::<Mod251, _>("251");
+    bench::<Mod65521, _>("65521");
+    bench::<Mod16777213, _>("16777213");
+    bench::<Mod998244353, _>("998244353");
+    bench::<Mod1073741789, _>("1073741789");
+}
@@ -0,0 +1,99 @@
+/*
+ * indent-stack
+ *
+ * Copyright (C) 2019 chankyin
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Times the basic operations of generated types across base widths and kinds of moduli, so
+//! that changes to the arithmetic can be compared before and after.
+//!
+//! Run with `cargo bench --bench ops`. Every figure is the mean time of one operation in
+//! nanoseconds. Inversion is timed once through `inv` and once for each algorithm applicable to
+//! the modulus, the latter on precomputed units so that every algorithm does the same work. `-`
+//! marks algorithms that do not apply or, for `euler_inv` on the moduli above 2^40, whose
+//! totient is too slow to compute.
+
+extern crate alloc;
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use generic_modular::{def_modular, gcd, Modular, WideModular};
+
+def_modular!(Mod101 : u16 | i16, 101 ; mod101_lbl);
+def_modular!(Mod1024 : u16 | i16, 1024 ; mod1024_lbl);
+def_modular!(Mod1000000 : u32 | i32, 1_000_000 ; mod1000000_lbl);
+def_modular!(Mod998244353 : u64 | i64, 998_244_353 ; mod998244353_lbl);
+def_modular!(Mod2p61m1 : u64 | i64, 2_305_843_009_213_693_951 ; mod2p61m1_lbl);
+def_modular!(Mod2p89m1 : u128 | i128, 618_970_019_642_690_137_449_562_111 ; mod2p89m1_lbl);
+
+const ROUNDS: u32 = 200_000;
+/// Exponentiation and inversion are orders of magnitude slower than addition on the wide moduli.
+const SLOW_ROUNDS: u32 = 5_000;
+/// The number of distinct units cycled through by the inversion algorithms.
+const UNITS: usize = 1024;
+
+fn time(rounds: u32, mut f: impl FnMut(u32) -> u128) -> f64 {
+    let start = Instant::now();
+    let mut acc = 0;
+    for i in 1..=rounds {
+        acc ^= f(i);
+    }
+    black_box(acc);
+    start.elapsed().as_nanos() as f64 / f64::from(rounds)
+}
+
+fn column(applies: bool, f: impl FnMut(u32) -> u128) -> String {
+    if applies {
+        format!("{:>8.1}", time(SLOW_ROUNDS, f))
+    } else {
+        format!("{:>8}", "-")
+    }
+}
+
+fn bench<M, T>(name: &str)
+where M: Modular<T> + WideModular, T: Copy + std::fmt::Debug + Default + std::ops::Add + std::ops::Sub + std::ops::Mul + std::ops::Rem {
+    let value = |i: u32| M::from_u128_reduced(u128::from(u64::from(i).wrapping_mul(0x9e37_79b9_7f4a_7c15)) % M::MOD_U128);
+    let residue = |x: Option<M>| x.map_or(0, |x| x.remainder_u128());
+    let pow2 = M::MOD_U128.is_power_of_two();
+    let units: Vec<M> = (1..).map(value).filter(|x| gcd(x.remainder_u128(), M::MOD_U128) == 1).take(UNITS).collect();
+    let unit = |i: u32| units[i as usize % UNITS];
+
+    let add = time(ROUNDS, |i| (black_box(value(i)) + black_box(value(i + 1))).remainder_u128());
+    let mul = time(ROUNDS, |i| (black_box(value(i)) * black_box(value(i + 1))).remainder_u128());
+    let pow = time(SLOW_ROUNDS, |i| black_box(value(i)).pow_u128(black_box(u128::from(i) << 40 | 0xffff)).remainder_u128());
+    let inv = time(SLOW_ROUNDS, |i| residue(black_box(value(i)).inv()));
+    let fermat = column(M::IS_FIELD, |i| residue(black_box(unit(i)).prime_inv()));
+    let egcd = column(true, |i| residue(black_box(unit(i)).coprime_inv()));
+    let newton = column(pow2, |i| residue(black_box(unit(i)).newton_inv()));
+    // the totient is computed by trial division the first time it is used, which is impractical
+    // for the wide primes, so `euler_inv` is only timed for the narrow moduli and after a warm-up
+    let narrow = M::MOD_U128 < 1 << 40;
+    if narrow {
+        black_box(unit(1).euler_inv());
+    }
+    let euler = column(narrow, |i| residue(black_box(unit(i)).euler_inv()));
+    println!("{:>12}: add {:>6.1}, mul {:>6.1}, pow {:>7.1}, inv {:>7.1} ({:?}); fermat {}, egcd {}, newton {}, euler {}",
+             name, add, mul, pow, inv, M::inverse_strategy_for_modulus(), fermat, egcd, newton, euler);
+}
+
+fn main() {
+    bench::<Mod101, _>("101");
+    bench::<Mod1024, _>("1024");
+    bench::<Mod1000000, _>("1000000");
+    bench::<Mod998244353, _>("998244353");
+    bench::<Mod2p61m1, _>("2^61 - 1");
+    bench::<Mod2p89m1, _>("2^89 - 1");
+}
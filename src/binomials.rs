@@ -0,0 +1,83 @@
+/*
+ * indent-stack
+ *
+ * Copyright (C) 2019 chankyin
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::ops::{Add, Mul, Rem, Sub};
+
+use crate::{Modular, Zero};
+
+/// A table of factorials and inverse factorials up to a limit, for computing binomial
+/// coefficients in constant time.
+///
+/// The table can only be built if the factorial of the limit is invertible, i.e. if the limit is
+/// less than the smallest prime factor of the modulus.
+#[derive(Clone, Debug)]
+pub struct Binomials<M> {
+    fact: Vec<M>,
+    inv_fact: Vec<M>,
+}
+
+impl<M> Binomials<M>
+where M: Copy + Zero + Mul<Output = M> {
+    /// Builds the tables of `0!, 1!, ..., limit!` and their inverses with a single inversion.
+    ///
+    /// # Returns
+    /// Returns `None` if `limit!` is not invertible modulo `MOD`.
+    pub fn new<T>(limit: usize) -> Option<Self>
+    where M: Modular<T>, T: Copy + Debug + Default + Add + Sub + Mul + Rem {
+        let mut fact = Vec::with_capacity(limit + 1);
+        fact.push(M::one());
+        for i in 1..=limit {
+            fact.push(fact[i - 1] * M::one().mul_scalar_wide(i as u128));
+        }
+        let mut inv_fact = Vec::with_capacity(limit + 1);
+        inv_fact.resize(limit + 1, M::zero());
+        inv_fact[limit] = fact[limit].inv()?;
+        for i in (1..=limit).rev() {
+            inv_fact[i - 1] = inv_fact[i] * M::one().mul_scalar_wide(i as u128);
+        }
+        Some(Self { fact, inv_fact })
+    }
+
+    /// The largest `n` supported by the table.
+    pub fn limit(&self) -> usize { self.fact.len() - 1 }
+
+    /// The factorial `n!`.
+    ///
+    /// # Panics
+    /// The function panics if `n` exceeds the limit.
+    pub fn factorial(&self, n: usize) -> M { self.fact[n] }
+
+    /// The inverse of the factorial `n!`.
+    ///
+    /// # Panics
+    /// The function panics if `n` exceeds the limit.
+    pub fn inv_factorial(&self, n: usize) -> M { self.inv_fact[n] }
+
+    /// The binomial coefficient `n choose k`, which is zero if `k > n`.
+    ///
+    /// # Panics
+    /// The function panics if `n` exceeds the limit.
+    pub fn choose(&self, n: usize, k: usize) -> M {
+        if k > n {
+            return M::zero();
+        }
+        self.fact[n] * self.inv_fact[k] * self.inv_fact[n - k]
+    }
+}
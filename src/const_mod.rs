@@ -0,0 +1,115 @@
+/*
+ * indent-stack
+ *
+ * Copyright (C) 2019 chankyin
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use core::marker::PhantomData;
+use core::ops::{Add, Mul, Neg, Sub};
+
+use crate::WideModular;
+
+/// A modular value with the modulus as a const generic parameter instead of a type generated by
+/// [`def_modular!`](macro.def_modular.html).
+///
+/// Arithmetic uses `u128` intermediates, so any positive `M` is supported. Convert from and to
+/// a generated type with the same modulus with [`from_modular`](#method.from_modular) and
+/// [`to_modular`](#method.to_modular), or with the generated `to_const_generic` and
+/// `from_const_generic` methods.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct ModU64<const M: u64>(u64);
+
+/// Asserts at compile time that the modulus of `W` is `M`.
+struct SameModulus<W, const M: u64>(PhantomData<W>);
+
+impl<W: WideModular, const M: u64> SameModulus<W, M> {
+    const CHECK: () = assert!(W::MOD_U128 == M as u128, "the moduli of the two modular types differ");
+}
+
+impl<const M: u64> ModU64<M> {
+    const VALID_MODULUS: () = assert!(M > 0, "the modulus must be positive");
+
+    /// Converts an integer into this modular type, reducing it modulo `M`.
+    pub const fn new(value: u64) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID_MODULUS;
+        Self(value % M)
+    }
+
+    /// The representative of the value in `[0, M)`.
+    pub const fn remainder(self) -> u64 { self.0 }
+
+    /// Converts a value of a generated modular type with the modulus `M`.
+    ///
+    /// Using a type with another modulus fails to compile.
+    pub fn from_modular<W: WideModular>(x: W) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = SameModulus::<W, M>::CHECK;
+        Self(x.remainder_u128() as u64)
+    }
+
+    /// Converts the value into a generated modular type with the modulus `M`.
+    ///
+    /// Using a type with another modulus fails to compile.
+    pub fn to_modular<W: WideModular>(self) -> W {
+        #[allow(clippy::let_unit_value)]
+        let () = SameModulus::<W, M>::CHECK;
+        W::from_u128_reduced(self.0 as u128)
+    }
+
+    /// Raises the value to the `n`th power by square-and-multiply.
+    pub fn pow(self, mut n: u64) -> Self {
+        let mut result = Self::new(1);
+        let mut base = self;
+        while n > 0 {
+            if n & 1 == 1 {
+                result = result * base;
+            }
+            base = base * base;
+            n >>= 1;
+        }
+        result
+    }
+}
+
+impl<const M: u64> Add for ModU64<M> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self { Self(((self.0 as u128 + rhs.0 as u128) % M as u128) as u64) }
+}
+
+impl<const M: u64> Sub for ModU64<M> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        if self.0 >= rhs.0 {
+            Self(self.0 - rhs.0)
+        } else {
+            Self(M - (rhs.0 - self.0))
+        }
+    }
+}
+
+impl<const M: u64> Mul for ModU64<M> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self { Self((self.0 as u128 * rhs.0 as u128 % M as u128) as u64) }
+}
+
+impl<const M: u64> Neg for ModU64<M> {
+    type Output = Self;
+
+    fn neg(self) -> Self { Self(0) - self }
+}
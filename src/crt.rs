@@ -0,0 +1,124 @@
+/*
+ * indent-stack
+ *
+ * Copyright (C) 2019 chankyin
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Chinese Remainder Theorem utilities for combining residues from different moduli.
+
+use crate::WideModular;
+
+/// Computes `a * b % m` without overflowing `u128`.
+#[doc(hidden)]
+pub fn mul_mod_u128(mut a: u128, mut b: u128, m: u128) -> u128 {
+    a %= m;
+    b %= m;
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+    let mut result = 0;
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod_u128(result, a, m);
+        }
+        a = add_mod_u128(a, a, m);
+        b >>= 1;
+    }
+    result
+}
+
+/// Computes `(a + b) % m` for `a, b < m` without overflowing `u128`.
+fn add_mod_u128(a: u128, b: u128, m: u128) -> u128 {
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+/// Computes the inverse of `a` modulo `m`, or `None` if they are not coprime.
+fn inv_mod_u128(a: u128, m: u128) -> Option<u128> {
+    // invariant: r0 == s0 * a (mod m), r1 == s1 * a (mod m)
+    let (mut r0, mut r1) = (m, a % m);
+    let (mut s0, mut s1) = (0_u128, 1_u128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        let r2 = r0 - q * r1;
+        let s2 = (s0 + m - mul_mod_u128(q, s1, m)) % m;
+        r0 = r1;
+        r1 = r2;
+        s0 = s1;
+        s1 = s2;
+    }
+    if r0 == 1 {
+        Some(s0 % m)
+    } else {
+        None
+    }
+}
+
+/// Combines residues `(r_i, m_i)` with pairwise coprime moduli into the unique value modulo the
+/// product of all `m_i`.
+///
+/// # Returns
+/// Returns `Some((value, modulus))`, where `value` is in `[0, modulus)` and `modulus` is the
+/// product of all moduli. Returns `None` if two moduli are not coprime or if the product of the
+/// moduli overflows `u128`.
+///
+/// An empty slice yields `Some((0, 1))`.
+pub fn crt_lift(residues: &[(u128, u128)]) -> Option<(u128, u128)> {
+    let mut value = 0_u128;
+    let mut modulus = 1_u128;
+    for &(r, m) in residues {
+        let r = r % m;
+        let combined = modulus.checked_mul(m)?;
+        // value + modulus * k == r (mod m)
+        let inv = inv_mod_u128(modulus % m, m)?;
+        let diff = (r + m - value % m) % m;
+        let k = mul_mod_u128(diff, inv, m);
+        value += modulus * k;
+        modulus = combined;
+    }
+    Some((value, modulus))
+}
+
+/// Reconstructs a value known to lie in `[0, value_bound)` from its residues `(r_i, m_i)` with
+/// pairwise coprime moduli.
+///
+/// The reconstruction is unique only if the product of the moduli is at least `value_bound`;
+/// otherwise several values in range share the same residues.
+///
+/// # Returns
+/// Returns `None` if the product of the moduli is less than `value_bound`, if the residues
+/// correspond to no value below `value_bound`, or in the cases where
+/// [`crt_lift`](fn.crt_lift.html) fails.
+pub fn crt_reconstruct_checked(residues: &[(u128, u128)], value_bound: u128) -> Option<u128> {
+    let (value, modulus) = crt_lift(residues)?;
+    if modulus < value_bound || value >= value_bound {
+        return None;
+    }
+    Some(value)
+}
+
+/// Combines one value of each of two modular types into the unique value modulo the product of
+/// their moduli.
+///
+/// This is the typed front end to [`crt_lift`](fn.crt_lift.html) for two moduli.
+///
+/// # Returns
+/// Returns `Some((value, modulus))` as in `crt_lift`, or `None` if the moduli are not coprime.
+pub fn crt_combine<A: WideModular, B: WideModular>(a: A, b: B) -> Option<(u128, u128)> {
+    crt_lift(&[(a.remainder_u128(), A::MOD_U128), (b.remainder_u128(), B::MOD_U128)])
+}
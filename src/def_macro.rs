@@ -20,6 +20,15 @@
 ///
 /// See [`ExampleModular101`](struct.ExampleModular101.html) for an example of what is generated.
 ///
+/// The constructors `from_signed`, `from_unsigned` and `from_u128` are `const fn`, so they can be
+/// used in `const` and `static` items. The `From` conversions are equivalent but not const.
+///
+/// `Default` is implemented as `Zero::zero()`, i.e. the residue `0`, which is canonical for every
+/// modulus because the modulus must be positive. Generic code may rely on the two agreeing.
+///
+/// The generated type implements `Ord` by comparing representatives in `[0, $mod)`. This ordering
+/// is not compatible with the arithmetic, but allows storing values in ordered collections.
+///
 /// # Parameters
 /// - `$name` is the name of the struct to be generated.
 /// - `$int` is the type of integer to work with. It must be one of `u8`, `u16`, `u32`, `u64` or
@@ -27,7 +36,12 @@
 /// - `$sint` is the type of integer to work with. It must be the signed version of `$int`, i.e.
 ///   `i8`, `i16`, `i32`, `i64` or `i128`. 
 /// - `$mod` is the modulus of this type of modular value. It must be a const value. It must
-///   satisfy * $mod < $int::max_value()` and `$mod * 2 < $sint::max_value()`.
+///   satisfy `$mod * 2 <= $sint::MAX`, or only `$mod <= $sint::MAX` with the `checked` feature,
+///   which makes multiplication always compute with `u128` intermediates at a small cost in
+///   speed. If `$mod * $mod` does not fit in `$int`, multiplication falls back to `u128`
+///   intermediates when the product overflows. If `$mod` is a power of two, addition and
+///   multiplication reduce by masking, and inverses of odd values are computed by Newton's
+///   iteration.
 /// - `$label` is a dummy label name for static assertions. This is unused on nightly builds with
 ///   the `underscore_const_names` feature.
 ///
@@ -36,6 +50,27 @@
 /// def_modular!(ExampleModular101 : u16 | i16, 101 ; some_random_label
 ///              #[doc = "your own documentation here"]);
 /// ```
+///
+/// The generated type supports the arithmetic operators, where division multiplies by the modular
+/// inverse:
+///
+/// ```
+/// extern crate alloc;
+/// use generic_modular::def_modular;
+///
+/// def_modular!(Mod101 : u16 | i16, 101 ; mod101);
+///
+/// fn main() {
+///     let (a, b) = (Mod101::from_signed(60), Mod101::from_signed(50));
+///     assert_eq!(a + b, Mod101::from_signed(9));
+///     assert_eq!(b - a, Mod101::from_signed(91));
+///     assert_eq!(-a, Mod101::from_signed(41));
+///     // 3000 = 29 * 101 + 71
+///     assert_eq!(a * b, Mod101::from_signed(71));
+///     assert_eq!(a / b * b, a);
+///     assert_eq!(Mod101::from_signed(-1), Mod101::from_signed(100));
+/// }
+/// ```
 #[macro_export]
 macro_rules! def_modular {
     ($name:ident : $int:ty | $sint:ty, $mod:expr ; $label:ident $(#[$docs:meta])*) => {
@@ -45,74 +80,707 @@ macro_rules! def_modular {
             use core::fmt::Debug;
             use core::ops::{Add, Div, Mul, Rem, Sub};
 
-            static_assertions::assert_impl!(impl_modular; $int, Copy, Debug, Default, Add, Sub, Mul, Rem);
+            static_assertions::assert_impl_all!(impl_modular; $int, Copy, Debug, Default, Add, Sub, Mul, Rem);
+            // Default and Zero both give the representative 0, which must be below the modulus
+            static_assertions::const_assert!(zero_check; ($mod as u128) > 0);
             static_assertions::const_assert!(overflow_check; {
-                ($mod as u128) <= (<$int>::max_value() as u128) &&
-                    ($mod as u128) + ($mod as u128) <= (<$sint>::max_value() as u128) &&
-                    ($mod as u128) <= (u64::max_value() as u128) && // self < u64::max is required for u128 (and automatically true for all other types)
-                    ($mod as u128) * ($mod as u128) < (<$int>::max_value() as u128) // squared must not overflow since it is less than u64::max
+                ($mod as u128) <= (<$int>::MAX as u128) && if $crate::CHECKED_ARITHMETIC {
+                    ($mod as u128) <= (<$sint>::MAX as u128)
+                } else {
+                    ($mod as u128) + ($mod as u128) <= (<$sint>::MAX as u128)
+                }
             });
 
             lazy_static::lazy_static! {
-                pub static ref IS_MOD_PRIME: bool = {
-                    $crate::is_prime($mod)
-                };
-
                 pub static ref MOD_PRIME_FACTOR_LIST: Vec<$int> = {
                     $crate::list_prime_factors($mod)
                 };
+
+                pub static ref GROUP_ORDER_FACTORS: Vec<$int> = {
+                    $crate::list_prime_factors($mod as $int - 1)
+                };
+
+                pub static ref GROUP_ORDER_DIVISORS: Vec<$int> = {
+                    let order = $mod as $int - 1;
+                    let mut divisors = Vec::new();
+                    divisors.push(1);
+                    for &p in GROUP_ORDER_FACTORS.iter() {
+                        let count = divisors.len();
+                        let mut power = p;
+                        while order % power == 0 {
+                            for i in 0..count {
+                                divisors.push(divisors[i] * power);
+                            }
+                            power = match power.checked_mul(p) {
+                                Some(power) => power,
+                                None => break,
+                            };
+                        }
+                    }
+                    divisors.sort_unstable();
+                    divisors
+                };
+
+                pub static ref MOD_TOTIENT: $int = {
+                    $crate::euler_totient($mod)
+                };
+
+                pub static ref MOD_CARMICHAEL: $int = {
+                    $crate::carmichael($mod)
+                };
+
+                pub static ref PRIMITIVE_ROOT: Option<super::$name> = {
+                    <super::$name as $crate::Modular<$int>>::primitive_root()
+                };
+
+                pub static ref TONELLI_SHANKS_SETUP: Option<(u128, u32, super::$name)> = {
+                    if <super::$name as $crate::Modular<$int>>::IS_FIELD && $mod as u128 > 2 {
+                        super::$name::tonelli_shanks_setup()
+                    } else {
+                        None
+                    }
+                };
             }
         }
 
         $(#[$docs])*
-        #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+        #[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
         pub struct $name($int);
 
+        #[allow(dead_code)]
         impl $name {
+            /// Whether `$mod` is a power of two, in which case reduction is a bitmask.
+            const IS_POW2: bool = ($mod as $int).is_power_of_two();
+
+            /// Whether `self^(MOD - 2)` is cheaper than the extended Euclidean algorithm.
+            const FERMAT_IS_CHEAP: bool = {
+                let exp = ($mod as $int).saturating_sub(2);
+                let cost = <$int>::BITS - exp.leading_zeros() + exp.count_ones();
+                cost <= $crate::FERMAT_INV_MAX_COST && ($mod as $int).checked_mul($mod as $int).is_some()
+            };
+
             /// Instantiated from the signed type, useful for literal instantiation.
             ///
             /// Use the `From` conversions instead if the input is not a literal.
             const fn new(mut int: $sint) -> Self {
+                Self::debug_assert_modulus();
                 int %= $mod; // now int is in (-$mod, $mod)
-                int += $mod; // now int is in (0, $mod * 2),
-                int %= $mod; // now int is in [0, $mod)
+                if int < 0 {
+                    int += $mod; // now int is in [0, $mod), without exceeding $mod
+                }
                 Self(int as $int)
             }
+
+            /// The residue `0`, which is also the value of `Default` and `Zero`.
+            pub const ZERO: Self = Self(0);
+
+            /// Converts a number of the signed type into this modular type.
+            ///
+            /// This is a `const fn`, so it can be used to build tables of modular constants.
+            pub const fn from_signed(int: $sint) -> Self { Self::new(int) }
+
+            /// Converts a number of the base type into this modular type.
+            ///
+            /// This is a `const fn`, so it can be used to build tables of modular constants.
+            pub const fn from_unsigned(int: $int) -> Self {
+                Self::debug_assert_modulus();
+                Self(int % $mod)
+            }
+
+            /// Wraps a representative without reducing it.
+            ///
+            /// The caller must ensure that `int < $mod`; otherwise, the arithmetic gives wrong
+            /// results. With the `verify` feature, every operator asserts this for its operands
+            /// and result.
+            pub const fn from_raw(int: $int) -> Self { Self(int) }
+
+            /// Converts the value into the const-generic [`ModU64`](struct.ModU64.html) with the
+            /// same modulus, e.g. `x.to_const_generic::<101>()`.
+            ///
+            /// Naming another modulus, or one that does not fit `u64`, fails to compile.
+            pub fn to_const_generic<const M: u64>(self) -> $crate::ModU64<M> { $crate::ModU64::from_modular(self) }
+
+            /// Converts a const-generic [`ModU64`](struct.ModU64.html) with the same modulus into
+            /// this type.
+            ///
+            /// Naming another modulus fails to compile.
+            pub fn from_const_generic<const M: u64>(x: $crate::ModU64<M>) -> Self { x.to_modular() }
+
+            /// Finds the square root of a nonzero quadratic residue modulo an odd prime with
+            /// Cipolla's algorithm, returning the one with the smaller representative.
+            ///
+            /// For the least `a` such that `a^2 - self` is a non-residue `d`, the root is
+            /// `(a + w)^((MOD + 1) / 2)` in the extension with `w^2 == d`.
+            fn cipolla(self) -> Option<Self> {
+                use $crate::Modular;
+
+                let (a, d) = (0..$mod).map(Self).map(|a| (a, a * a - self)).find(|&(_, d)| d.legendre() == Some(-1))?;
+                let root = $crate::QuadExt::new(a, Self(1), d).pow(($mod as u128).div_ceil(2)).re();
+                Some(root.min(Self(0) - root))
+            }
+
+            /// The parts of Tonelli-Shanks that depend only on the modulus: `MOD - 1 = q * 2^s`
+            /// with `q` odd, and `z^q` for the least quadratic non-residue `z`.
+            fn tonelli_shanks_setup() -> Option<(u128, u32, Self)> {
+                let mut q = $mod as u128 - 1;
+                let mut s = 0;
+                while q % 2 == 0 {
+                    q /= 2;
+                    s += 1;
+                }
+                let z = (2..$mod).map(Self).find(|z| <Self as $crate::Modular<$int>>::legendre(*z) == Some(-1))?;
+                Some((q, s, <Self as $crate::Modular<$int>>::pow_u128(z, q)))
+            }
+
+            /// Finds the square root of a nonzero quadratic residue modulo an odd prime, returning
+            /// the one with the smaller representative.
+            fn tonelli_shanks(self, (q, s, z_q): (u128, u32, Self)) -> Self {
+                use $crate::Modular;
+
+                let mut m = s;
+                let mut c = z_q;
+                let mut t = self.pow_u128(q);
+                let mut root = self.pow_u128(q / 2 + 1);
+                while t != Self(1) {
+                    // find the least i such that t^(2^i) == 1
+                    let mut i = 0;
+                    let mut t2i = t;
+                    while t2i != Self(1) {
+                        t2i = t2i * t2i;
+                        i += 1;
+                    }
+                    let mut b = c;
+                    for _ in 0..(m - i - 1) {
+                        b = b * b;
+                    }
+                    m = i;
+                    c = b * b;
+                    t *= c;
+                    root *= b;
+                }
+                let root = root.min(Self(0) - root);
+                debug_assert!(root * root == self, "Tonelli-Shanks found {:?} as a square root of {:?}", root, self);
+                root
+            }
+
+            /// Asserts in debug builds that the modulus is at least 2.
+            ///
+            /// Modulo 1 every value is zero, which the reductions handle, but such a type is
+            /// almost certainly a mistake, so the constructors, operators and `pow_u128` check it.
+            const fn debug_assert_modulus() {
+                debug_assert!($mod as u128 >= 2, "modular type with the degenerate modulus 1");
+            }
+
+            /// Asserts that the representative is below `$mod` if the `verify` feature is enabled.
+            ///
+            /// This also checks the modulus with `debug_assert_modulus`.
+            fn verified(self) -> Self {
+                Self::debug_assert_modulus();
+                if $crate::VERIFY_INVARIANTS {
+                    assert!(self.0 < $mod, "{:?} is not a canonical representative modulo {:?}", self.0, $mod as $int);
+                }
+                self
+            }
+
+            /// Converts a `u128` into this modular type, reducing it modulo `$mod`.
+            ///
+            /// This is a `const fn`, so it can be used to build tables of modular constants.
+            pub const fn from_u128(int: u128) -> Self {
+                Self::debug_assert_modulus();
+                Self((int % ($mod as u128)) as $int)
+            }
+
+            /// Returns the balanced representative of the value, i.e. the one in
+            /// `(-$mod / 2, $mod / 2]`.
+            pub const fn signed_value(self) -> $sint {
+                if self.0 > $mod / 2 {
+                    self.0 as $sint - $mod as $sint
+                } else {
+                    self.0 as $sint
+                }
+            }
+
+            /// Returns both the representative in `[0, $mod)` and the balanced representative of
+            /// [`signed_value`](#method.signed_value).
+            pub const fn canonical_and_balanced(self) -> ($int, $sint) { (self.0, self.signed_value()) }
+
+            /// Converts the representative to `f64`, rounding if it exceeds 53 bits.
+            ///
+            /// This is meant for plotting and debugging, not for further arithmetic.
+            pub fn to_f64_representative(self) -> f64 { self.0 as f64 }
+
+            /// Converts the balanced representative from [`signed_value`](#method.signed_value) to
+            /// `f64`, rounding if it exceeds 53 bits.
+            pub fn to_f64_balanced(self) -> f64 { self.signed_value() as f64 }
+
+            /// Maps each value to its balanced representative with
+            /// [`signed_value`](#method.signed_value).
+            pub fn to_balanced_slice(items: &[Self]) -> alloc::vec::Vec<$sint> {
+                items.iter().map(|x| x.signed_value()).collect()
+            }
+
+            /// Sums the rationals `num / den` given as pairs, inverting all denominators together
+            /// with [`batch_inv`](fn.batch_inv.html).
+            ///
+            /// # Returns
+            /// Returns `None` if any denominator is not invertible modulo `$mod`.
+            pub fn sum_rationals(pairs: &[($sint, $sint)]) -> Option<Self> {
+                let dens: alloc::vec::Vec<Self> = pairs.iter().map(|&(_, den)| Self::new(den)).collect();
+                let invs = $crate::batch_inv(&dens)?;
+                Some(pairs.iter().zip(invs).fold(Self(0), |acc, (&(num, _), inv)| acc + Self::new(num) * inv))
+            }
+
+            /// Extended Euclidean inverse without the coprimality debug assertion.
+            fn coprime_inv_unchecked(self) -> Option<Self> {
+                let (x, _, g) = $crate::extended_gcd(self.0 as $sint, $mod as $sint);
+                if g == 1 {
+                    Some(Self::new(x))
+                } else {
+                    None
+                }
+            }
+
+            /// 2-adic inverse by Newton's iteration, assuming that `$mod` is a power of two and the
+            /// value is odd.
+            fn newton_inv_unchecked(self) -> Self {
+                let a = self.0;
+                // every odd `a` satisfies `a * a = 1 (mod 8)`, so `a` is correct to 3 bits
+                let mut x = a;
+                let mut bits = 3;
+                while bits < <$int>::BITS {
+                    x = x.wrapping_mul((2 as $int).wrapping_sub(a.wrapping_mul(x)));
+                    bits *= 2;
+                }
+                Self(x & ($mod as $int - 1))
+            }
+
+            /// Encodes the representative as little-endian bytes of the base type.
+            pub const fn to_le_bytes(self) -> [u8; ::core::mem::size_of::<$int>()] { self.0.to_le_bytes() }
+
+            /// Decodes little-endian bytes of the base type, reducing the decoded integer.
+            pub const fn from_le_bytes(bytes: [u8; ::core::mem::size_of::<$int>()]) -> Self {
+                Self::from_unsigned(<$int>::from_le_bytes(bytes))
+            }
+
+            /// Encodes the representative as big-endian bytes of the base type.
+            pub const fn to_be_bytes(self) -> [u8; ::core::mem::size_of::<$int>()] { self.0.to_be_bytes() }
+
+            /// Decodes big-endian bytes of the base type, reducing the decoded integer.
+            pub const fn from_be_bytes(bytes: [u8; ::core::mem::size_of::<$int>()]) -> Self {
+                Self::from_unsigned(<$int>::from_be_bytes(bytes))
+            }
+
+            /// Encodes the balanced representative from [`signed_value`](#method.signed_value) as
+            /// little-endian bytes of the signed type.
+            pub const fn to_balanced_le_bytes(self) -> [u8; ::core::mem::size_of::<$sint>()] {
+                self.signed_value().to_le_bytes()
+            }
+
+            /// Decodes little-endian bytes of the signed type, reducing the decoded integer.
+            pub const fn from_balanced_le_bytes(bytes: [u8; ::core::mem::size_of::<$sint>()]) -> Self {
+                Self::new(<$sint>::from_le_bytes(bytes))
+            }
         }
 
         impl $crate::Modular<$int> for $name {
             const MOD: $int = $mod;
 
+            const IS_FIELD: bool = $crate::const_is_prime($mod as u128);
+
+            const INV_2: Option<Self> = if Self::MOD % 2 == 1 { Some(Self((Self::MOD / 2 + 1) % Self::MOD)) } else { None };
+
             fn remainder(&self) -> $int { self.0 }
 
-            fn pow(self, n: usize) -> Self {
-                if n == 0 {
-                    return Self(1);
+            fn to_usize(self) -> usize {
+                debug_assert!(self.0 as u128 <= usize::MAX as u128, "{} does not fit in usize", self.0);
+                self.0 as usize
+            }
+
+            fn rep_is_even(self) -> bool { self.0 % 2 == 0 }
+
+            fn try_from_i128(x: i128) -> Result<Self, $crate::ModularError> {
+                Ok(Self::from_u128(x.rem_euclid($mod as i128) as u128))
+            }
+
+            fn from_rational(num: i128, den: i128) -> Option<Self> {
+                let num = Self::try_from_i128(num).ok()?;
+                let den = Self::try_from_i128(den).ok()?;
+                den.inv().map(|inv| num * inv)
+            }
+
+            fn rational_reconstruct(self) -> Option<(i128, u128)> {
+                let bound = (Self::MOD as u128 / 2).isqrt() as i128;
+                let (mut r0, mut r1) = (Self::MOD as i128, self.0 as i128);
+                let (mut s0, mut s1) = (0_i128, 1_i128);
+                while r1 > bound {
+                    let q = r0 / r1;
+                    let r2 = r0 - q * r1;
+                    r0 = r1;
+                    r1 = r2;
+                    let s2 = s0 - q * s1;
+                    s0 = s1;
+                    s1 = s2;
+                }
+                let (num, den) = if s1 < 0 { (-r1, -s1) } else { (r1, s1) };
+                if den == 0 || den > bound || $crate::gcd(num.unsigned_abs(), den as u128) != 1 {
+                    return None;
+                }
+                Some((num, den as u128))
+            }
+
+            fn residue_bits(self) -> alloc::vec::Vec<bool> {
+                let width = <$int>::BITS - ($mod as $int - 1).leading_zeros();
+                (0..width).map(|i| (self.0 >> i) & 1 == 1).collect()
+            }
+
+            fn into_bits_le(self, width: u32) -> Option<alloc::vec::Vec<bool>> {
+                if width < <$int>::BITS && self.0 >> width != 0 {
+                    return None;
                 }
-                let root = self.pow(n / 2);
-                let result = root * root;
-                if n % 1 == 1 {
-                    result * self
+                Some((0..width).map(|i| i < <$int>::BITS && (self.0 >> i) & 1 == 1).collect())
+            }
+
+            fn from_bits_le(bits: &[bool]) -> Option<Self> {
+                let mut value: $int = 0;
+                for (i, _) in bits.iter().enumerate().filter(|&(_, &bit)| bit) {
+                    if i >= <$int>::BITS as usize {
+                        return None;
+                    }
+                    value |= 1 << i;
+                }
+                if value < $mod {
+                    Some(Self(value))
                 } else {
-                    result
+                    None
                 }
             }
 
-            fn prime_inv(self) -> Self {
-                assert!(*$label::IS_MOD_PRIME);
-                self.pow($mod - 2)
+            fn from_digits(digits: &[$int], base: $int) -> Self {
+                let base = Self::from(base);
+                digits.iter().fold(Self(0), |acc, &d| acc * base + Self::from(d))
             }
 
-            fn coprime_inv(self) -> Option<Self> {
-                assert!($label::MOD_PRIME_FACTOR_LIST.iter().all(|f| self.0 % f != 0));
-                let (x, _, g) = $crate::extended_gcd(self.0, $mod);
-                if g == 1 {
-                    Some(Self(x))
+            fn try_from_digits(digits: &[$int], base: $int) -> Option<Self> {
+                if digits.iter().any(|&d| d >= base) {
+                    return None;
+                }
+                Some(Self::from_digits(digits, base))
+            }
+
+            fn sum_raw_checked<I: IntoIterator<Item = $int>>(iter: I) -> Self {
+                let mut acc = 0_u128;
+                for x in iter {
+                    acc = match acc.checked_add(x as u128) {
+                        Some(sum) => sum,
+                        // both terms are reduced below $mod, so this cannot overflow again
+                        None => acc % ($mod as u128) + (x as u128) % ($mod as u128),
+                    };
+                }
+                Self::from_u128(acc)
+            }
+
+            fn reduce_slice_raw(xs: &mut [$int]) {
+                for x in xs {
+                    *x %= $mod;
+                }
+            }
+
+            fn fast_sum(items: &[Self]) -> Self {
+                // each representative is below $mod, so this many fit in the accumulator
+                let chunk = (u128::MAX / ($mod as u128)).min(usize::MAX as u128) as usize;
+                items.chunks(chunk)
+                    .map(|c| Self::from_u128(c.iter().map(|x| x.0 as u128).sum::<u128>()))
+                    .fold(Self(0), |acc, x| acc + x)
+            }
+
+            fn convolve(a: &[Self], b: &[Self]) -> alloc::vec::Vec<Self> {
+                let size = (a.len() + b.len()).saturating_sub(1).next_power_of_two() as u128;
+                if !Self::IS_FIELD || a.len().min(b.len()) < $crate::NTT_THRESHOLD
+                    || ($mod as u128 - 1) % size != 0 {
+                    return $crate::convolve_schoolbook(a, b);
+                }
+                match Self::cached_primitive_root() {
+                    Some(g) => $crate::convolve_ntt(a, b, g.pow_u128(($mod as u128 - 1) / size)),
+                    None => $crate::convolve_schoolbook(a, b),
+                }
+            }
+
+            fn convolve_arbitrary(a: &[Self], b: &[Self]) -> alloc::vec::Vec<Self> {
+                let reps = |x: &[Self]| x.iter().map(|v| v.0 as u128).collect::<alloc::vec::Vec<_>>();
+                match $crate::convolve_crt(&reps(a), &reps(b), $mod as u128) {
+                    Some(c) => c.into_iter().map(Self::from_u128).collect(),
+                    None => $crate::convolve_schoolbook(a, b),
+                }
+            }
+
+            fn dft_matrix(n: usize) -> Option<$crate::Matrix<Self>> {
+                if !Self::IS_FIELD || n == 0 || ($mod as u128 - 1) % n as u128 != 0 {
+                    return None;
+                }
+                let root = Self::cached_primitive_root()?.pow_u128(($mod as u128 - 1) / n as u128);
+                Some($crate::Matrix::from_fn(n, n, |i, j| root.pow_u128((i * j % n) as u128)))
+            }
+
+            fn mul_scalar_wide(self, k: u128) -> Self {
+                Self::from_u128($crate::mul_mod_u128(self.0 as u128, k % $mod as u128, $mod as u128))
+            }
+
+            fn pow(self, n: usize) -> Self { self.pow_u128(n as u128) }
+
+            fn pow_u128(self, n: u128) -> Self {
+                Self::debug_assert_modulus();
+                if self.0 == 1 {
+                    return self;
+                }
+                if Self::IS_FIELD && self.0 != 0 && n >= $mod as u128 - 1 {
+                    self.pow_mod_order(n)
+                } else {
+                    $crate::pow_hybrid(self, n)
+                }
+            }
+
+            fn pow_mod_order(self, exp: u128) -> Self {
+                if !Self::IS_FIELD {
+                    return self.pow_mod_order_with(exp, *$label::MOD_TOTIENT as u128);
+                }
+                if self.0 == 0 {
+                    // zero is not a unit, so its exponent must not be reduced
+                    return if exp == 0 { Self(1) } else { Self(0) };
+                }
+                match exp % ($mod as u128 - 1) {
+                    0 => Self(1),
+                    exp => $crate::pow_hybrid(self, exp),
+                }
+            }
+
+            fn pow_mod_order_with(self, exp: u128, totient: u128) -> Self {
+                assert!(totient != 0, "the totient must be nonzero");
+                if $crate::gcd(self.0, $mod) == 1 {
+                    self.pow_u128(exp % totient)
+                } else {
+                    self.pow_u128(exp)
+                }
+            }
+
+            fn pow_reduced_carmichael(self, exp: u128) -> Option<Self> {
+                if $crate::gcd(self.0, $mod) != 1 {
+                    return None;
+                }
+                Some(self.pow_u128(exp % *$label::MOD_CARMICHAEL as u128))
+            }
+
+            fn pow_elem(self, exp: Self) -> Self { self.pow_mod_order(exp.0 as u128) }
+
+            fn power_tower(values: &[u128]) -> Self {
+                Self::from_u128($crate::power_tower_mod(values, $mod as u128))
+            }
+
+            fn legendre(self) -> Option<i8> {
+                if !Self::IS_FIELD {
+                    return None;
+                }
+                if self.0 == 0 {
+                    return Some(0);
+                }
+                if Self::MOD == 2 || self.pow_u128((Self::MOD as u128 - 1) / 2) == Self(1) {
+                    Some(1)
+                } else {
+                    Some(-1)
+                }
+            }
+
+            fn sqrt(self) -> Option<Self> {
+                if self.legendre()? == -1 {
+                    return None;
+                }
+                if self.0 == 0 || Self::MOD == 2 {
+                    return Some(self);
+                }
+                let root = if Self::MOD % 4 == 3 {
+                    let root = self.pow_u128(($mod as u128 + 1) / 4);
+                    root.min(Self(0) - root)
+                } else {
+                    let setup = (*$label::TONELLI_SHANKS_SETUP)?;
+                    if setup.1 < $crate::CIPOLLA_MIN_TWO_ADICITY {
+                        self.tonelli_shanks(setup)
+                    } else {
+                        self.cipolla()?
+                    }
+                };
+                debug_assert!(root * root == self, "found {:?} as a square root of {:?}", root, self);
+                Some(root)
+            }
+
+            fn sqrt_many(items: &[Self]) -> alloc::vec::Vec<Option<Self>> {
+                if !Self::IS_FIELD {
+                    return alloc::vec![None; items.len()];
+                }
+                items.iter().map(|&x| x.sqrt()).collect()
+            }
+
+            fn sqrt_3mod4(self) -> Option<Self> {
+                if !Self::IS_FIELD || Self::MOD % 4 != 3 {
+                    return None;
+                }
+                let root = self.pow_u128(($mod as u128 + 1) / 4);
+                if root * root == self {
+                    Some(root.min(Self(0) - root))
                 } else {
                     None
                 }
             }
 
+            fn has_kth_root(self, k: usize) -> Option<bool> {
+                if !Self::IS_FIELD {
+                    return None;
+                }
+                if k == 0 {
+                    return Some(self == Self(1));
+                }
+                if self.0 == 0 {
+                    return Some(true);
+                }
+                let order = Self::MOD as u128 - 1;
+                Some(self.pow_u128(order / $crate::gcd(k as u128, order)) == Self(1))
+            }
+
+            fn modulus_is_safe_prime() -> bool {
+                Self::IS_FIELD && $crate::const_is_prime((Self::MOD as u128 - 1) / 2)
+            }
+
+            fn group_order_factors() -> &'static [$int] { &$label::GROUP_ORDER_FACTORS }
+
+            fn group_order_divisors() -> &'static [$int] { &$label::GROUP_ORDER_DIVISORS }
+
+            fn order(self) -> Option<$int> {
+                if !Self::IS_FIELD || self.0 == 0 {
+                    return None;
+                }
+                Self::group_order_divisors().iter().copied().find(|&d| self.pow_u128(d as u128) == Self(1))
+            }
+
+            fn is_primitive_root(self) -> bool {
+                if !Self::IS_FIELD || self.0 == 0 {
+                    return false;
+                }
+                let order = Self::MOD - 1;
+                Self::group_order_factors().iter().all(|&q| self.pow_u128((order / q) as u128) != Self(1))
+            }
+
+            fn primitive_root() -> Option<Self> {
+                if !Self::IS_FIELD {
+                    return None;
+                }
+                (1..$mod).map(Self).find(|g| g.is_primitive_root())
+            }
+
+            fn cached_primitive_root() -> Option<Self> { *$label::PRIMITIVE_ROOT }
+
+            fn discrete_log(base: Self, target: Self) -> Option<usize> {
+                if !Self::IS_FIELD {
+                    return None;
+                }
+                // a zero base has no order, but then the result does not depend on it
+                let order = base.order().unwrap_or(1) as u128;
+                $crate::discrete_log_bsgs(base, target, order).and_then(|k| <usize as ::core::convert::TryFrom<u128>>::try_from(k).ok())
+            }
+
+            fn discrete_log_ph(base: Self, target: Self) -> Option<usize> {
+                if !Self::IS_FIELD {
+                    return None;
+                }
+                let order = base.order().unwrap_or(1) as u128;
+                let factors: alloc::vec::Vec<u128> = Self::group_order_factors().iter().map(|&q| q as u128).collect();
+                $crate::discrete_log_pohlig_hellman(base, target, order, &factors).and_then(|k| <usize as ::core::convert::TryFrom<u128>>::try_from(k).ok())
+            }
+
+            fn prime_inv(self) -> Option<Self> {
+                if !Self::IS_FIELD || self.0 == 0 {
+                    return None;
+                }
+                Some(self.pow_u128((Self::MOD as u128).saturating_sub(2)))
+            }
+
+            fn coprime_inv(self) -> Option<Self> {
+                debug_assert!($crate::gcd(self.0, $mod) == 1);
+                self.coprime_inv_unchecked()
+            }
+
+            fn sigma(n: $int) -> Self {
+                assert!(n != 0, "Zero has infinitely many divisors");
+                $crate::list_prime_factors(n).into_iter().map(|p| {
+                    let (mut m, step) = (n, Self::from(p));
+                    let (mut sum, mut power) = (Self(1 % $mod), Self(1 % $mod));
+                    while m % p == 0 {
+                        m /= p;
+                        power *= step;
+                        sum += power;
+                    }
+                    sum
+                }).fold(Self(1 % $mod), |acc, sum| acc * sum)
+            }
+
+            fn nilpotency_index(self) -> Option<usize> {
+                if $label::MOD_PRIME_FACTOR_LIST.iter().any(|&p| self.0 % p != 0) {
+                    return None;
+                }
+                // the index is at most the largest prime exponent of $mod, which is below 128
+                let mut power = self;
+                let mut index = 1;
+                while power.0 != 0 {
+                    power *= self;
+                    index += 1;
+                }
+                Some(index)
+            }
+
+            fn inv_table() -> Option<alloc::vec::Vec<Self>> {
+                if !Self::IS_FIELD {
+                    return None;
+                }
+                let mut table = alloc::vec::Vec::with_capacity(Self::MOD as usize);
+                table.push(Self(0));
+                if Self::MOD > 1 {
+                    table.push(Self(1));
+                }
+                for i in 2..$mod {
+                    // MOD = (MOD / i) * i + MOD % i, so 1 / i = -(MOD / i) / (MOD % i)
+                    let inv = Self(0) - Self($mod / i) * table[($mod % i) as usize];
+                    table.push(inv);
+                }
+                Some(table)
+            }
+
+            fn inv_table_checked() -> alloc::vec::Vec<Option<Self>> {
+                let size = Self::MOD as usize;
+                let mut table = alloc::vec::Vec::with_capacity(size);
+                table.push(None);
+                if size > 1 {
+                    table.push(Some(Self(1)));
+                }
+                let spf = $crate::smallest_prime_factor_sieve(size - 1);
+                for i in 2..size {
+                    let p = spf[i];
+                    let inv = if p == i {
+                        Self(i as $int).coprime_inv_unchecked()
+                    } else {
+                        match (table[p], table[i / p]) {
+                            (Some(a), Some(b)) => Some(a * b),
+                            _ => None,
+                        }
+                    };
+                    table.push(inv);
+                }
+                table
+            }
+
+            fn euler_inv(self) -> Option<Self> {
+                if $crate::gcd(self.0, $mod) != 1 {
+                    return None;
+                }
+                Some(self.pow_u128(*$label::MOD_TOTIENT as u128 - 1))
+            }
+
             fn brute_force_inv(self) -> Option<Self> {
                 for i in 1..$mod {
                     if self * Self(i) == Self(1) {
@@ -122,15 +790,64 @@ macro_rules! def_modular {
                 None
             }
 
-            fn inv(self) -> Option<Self> {
-                if *$label::IS_MOD_PRIME {
-                    Some(self.prime_inv())
-                } else if $label::MOD_PRIME_FACTOR_LIST.iter().all(|f| self.0 % f != 0) {
-                    self.coprime_inv()
+            fn newton_inv(self) -> Option<Self> {
+                if Self::IS_POW2 && self.0 % 2 == 1 {
+                    Some(self.newton_inv_unchecked())
+                } else {
+                    None
+                }
+            }
+
+            fn inverse_strategy_for_modulus() -> $crate::InvStrategy {
+                if Self::IS_FIELD && Self::FERMAT_IS_CHEAP {
+                    $crate::InvStrategy::Fermat
+                } else if Self::IS_FIELD {
+                    $crate::InvStrategy::ExtendedGcd
+                } else if Self::IS_POW2 {
+                    $crate::InvStrategy::Newton
                 } else {
-                    self.brute_force_inv()
+                    $crate::InvStrategy::ExtendedGcd
                 }
             }
+
+            fn inv(self) -> Option<Self> {
+                if self.0 == 0 {
+                    return None;
+                }
+                match Self::inverse_strategy_for_modulus() {
+                    $crate::InvStrategy::Fermat => Some(self.pow_u128((Self::MOD as u128).saturating_sub(2))),
+                    $crate::InvStrategy::Newton => self.newton_inv(),
+                    $crate::InvStrategy::ExtendedGcd => self.coprime_inv_unchecked(),
+                }
+            }
+        }
+
+        impl $crate::WideModular for $name {
+            const MOD_U128: u128 = $mod as u128;
+
+            fn remainder_u128(self) -> u128 { self.0 as u128 }
+
+            fn from_u128_reduced(x: u128) -> Self { Self::from_u128(x) }
+        }
+
+        impl $crate::DynModularOps for $name {
+            fn modulus(&self) -> u128 { $mod as u128 }
+
+            fn residue(&self) -> u128 { self.0 as u128 }
+
+            fn is_field(&self) -> bool { <Self as $crate::Modular<$int>>::IS_FIELD }
+
+            fn add_residue(&self, rhs: u128) -> u128 { (*self + Self::from_u128(rhs)).0 as u128 }
+
+            fn mul_residue(&self, rhs: u128) -> u128 { (*self * Self::from_u128(rhs)).0 as u128 }
+
+            fn pow_residue(&self, n: u128) -> u128 {
+                <Self as $crate::Modular<$int>>::pow_u128(*self, n).0 as u128
+            }
+
+            fn inv_residue(&self) -> Option<u128> {
+                <Self as $crate::Modular<$int>>::inv(*self).map(|inv| inv.0 as u128)
+            }
         }
 
         /// Converts a number of the base type into this modular type.
@@ -146,30 +863,137 @@ macro_rules! def_modular {
         /// Converts a number of the signed type into this modular type.
         ///
         /// Use the `new` method instead for literal inputs, because the compiler canot determine
-        /// if the signed `From` or the unsigned `From` is intended.
+        /// if the signed `From` or the unsigned `From` is intended. For unsigned inputs wider than
+        /// the signed type, use `from_u128` or `WideModular::from_u128_reduced` instead.
         impl From<$sint> for $name {
             fn from(int: $sint) -> Self { Self::new(int) }
         }
 
+        /// Displays the representative in `[0, $mod)` without the modulus.
+        ///
+        /// Use [`Annotated`](struct.Annotated.html) to include the modulus.
+        impl ::core::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                ::core::fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        /// Converts a nonzero number of the base type into this modular type.
+        ///
+        /// The value is reduced, so the result may be zero.
+        impl From<::core::num::NonZero<$int>> for $name {
+            fn from(int: ::core::num::NonZero<$int>) -> Self { Self::from(int.get()) }
+        }
+
+        /// Converts little-endian bytes of the base type width into this modular type, reducing
+        /// the value.
+        ///
+        /// This is the same as `from_le_bytes`, e.g. for a hash digest truncated to the width.
+        impl From<[u8; ::core::mem::size_of::<$int>()]> for $name {
+            fn from(bytes: [u8; ::core::mem::size_of::<$int>()]) -> Self { Self::from_le_bytes(bytes) }
+        }
+
+        /// Converts an ASCII decimal digit character into its value.
+        impl ::core::convert::TryFrom<char> for $name {
+            type Error = $crate::ModularError;
+
+            fn try_from(c: char) -> Result<Self, Self::Error> {
+                match c.to_digit(10) {
+                    Some(d) => Ok(Self::from_u128(d as u128)),
+                    None => Err($crate::ModularError::InvalidDigit(c)),
+                }
+            }
+        }
+
+        /// Parses a decimal integer with an optional sign, reducing it modulo `$mod`.
+        ///
+        /// Unlike `FromStr` for the primitive integers, surrounding whitespace is trimmed, and
+        /// inputs of any length are accepted since they are reduced digit by digit.
+        impl ::core::str::FromStr for $name {
+            type Err = $crate::ModularError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let s = s.trim();
+                let (negative, digits) = match s.as_bytes().first() {
+                    Some(b'-') => (true, &s[1..]),
+                    Some(b'+') => (false, &s[1..]),
+                    _ => (false, s),
+                };
+                if digits.is_empty() {
+                    return Err($crate::ModularError::Empty);
+                }
+                let ten = Self::from_u128(10);
+                let mut value = Self(0);
+                for c in digits.chars() {
+                    value = value * ten + <Self as ::core::convert::TryFrom<char>>::try_from(c)?;
+                }
+                Ok(if negative { Self(0) - value } else { value })
+            }
+        }
+
         impl ::core::ops::Add for $name {
             type Output = Self;
 
-            fn add(self, rhs: Self) -> Self { Self((self.0 + rhs.0) % $mod) }
+            fn add(self, rhs: Self) -> Self {
+                let (lhs, rhs) = (self.verified(), rhs.verified());
+                let sum = if Self::IS_POW2 {
+                    Self((lhs.0 + rhs.0) & ($mod as $int - 1))
+                } else {
+                    // both operands are below $mod <= $sint::MAX, so the sum fits in $int and one
+                    // subtraction reduces it
+                    let sum = lhs.0 + rhs.0;
+                    Self(if sum >= $mod { sum - $mod } else { sum })
+                };
+                sum.verified()
+            }
         }
 
         impl ::core::ops::Sub for $name {
             type Output = Self;
 
             fn sub(self, rhs: Self) -> Self {
-                // add $mod to prevent negative integer overflow
-                Self(((self.0 + $mod) - rhs.0) % $mod)
+                let (lhs, rhs) = (self.verified(), rhs.verified());
+                // branch instead of adding $mod first, so that no intermediate exceeds $mod
+                let diff = if lhs.0 >= rhs.0 {
+                    Self(lhs.0 - rhs.0)
+                } else {
+                    Self($mod - (rhs.0 - lhs.0))
+                };
+                diff.verified()
             }
         }
 
+        impl ::core::ops::Neg for $name {
+            type Output = Self;
+
+            fn neg(self) -> Self { Self(0) - self }
+        }
+
+        impl<'a> ::core::ops::Neg for &'a $name {
+            type Output = $name;
+
+            fn neg(self) -> $name { -*self }
+        }
+
         impl ::core::ops::Mul for $name {
             type Output = Self;
 
-            fn mul(self, rhs: Self) -> Self { Self((self.0 * rhs.0) % $mod) }
+            #[allow(clippy::suspicious_arithmetic_impl)]
+            fn mul(self, rhs: Self) -> Self {
+                let (lhs, rhs) = (self.verified(), rhs.verified());
+                let product = if $crate::CHECKED_ARITHMETIC {
+                    Self::from_u128($crate::mul_mod_u128(lhs.0 as u128, rhs.0 as u128, $mod as u128))
+                } else if Self::IS_POW2 {
+                    // $mod divides 2^BITS, so the wrapped product has the right residue
+                    Self(lhs.0.wrapping_mul(rhs.0) & ($mod as $int - 1))
+                } else {
+                    match lhs.0.checked_mul(rhs.0) {
+                        Some(product) => Self(product % $mod),
+                        None => Self::from_u128($crate::mul_mod_u128(lhs.0 as u128, rhs.0 as u128, $mod as u128)),
+                    }
+                };
+                product.verified()
+            }
         }
 
         /// Multiplies the modular inverse of the right operand.
@@ -179,20 +1003,97 @@ macro_rules! def_modular {
         impl ::core::ops::Div for $name {
             type Output = Self;
 
+            #[allow(clippy::suspicious_arithmetic_impl)]
             fn div(self, rhs: Self) -> Self {
                 use $crate::Modular;
-                self * rhs.inv().expect("Modular inverse does not exist for rhs")
+                match rhs.inv() {
+                    Some(inv) => self * inv,
+                    None => panic!("Modular inverse of {} does not exist modulo {}", rhs, $mod as $int),
+                }
             }
         }
 
+        impl ::core::ops::AddAssign for $name {
+            fn add_assign(&mut self, rhs: Self) { *self = *self + rhs; }
+        }
+
+        impl ::core::ops::SubAssign for $name {
+            fn sub_assign(&mut self, rhs: Self) { *self = *self - rhs; }
+        }
+
+        impl ::core::ops::MulAssign for $name {
+            fn mul_assign(&mut self, rhs: Self) { *self = *self * rhs; }
+        }
+
+        /// Multiplies the modular inverse of the right operand in place.
+        ///
+        /// # Panics
+        /// If modular inverse does not exist for the right operand, the function panicks.
+        impl ::core::ops::DivAssign for $name {
+            fn div_assign(&mut self, rhs: Self) { *self = *self / rhs; }
+        }
+
+        /// Sums the values under the modulus.
+        ///
+        /// Through the standard library, this also allows summing an iterator of
+        /// `Result<Self, E>` into `Result<Self, E>`, short-circuiting on the first error.
+        impl ::core::iter::Sum for $name {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self(0), |acc, x| acc + x)
+            }
+        }
+
+        impl<'a> ::core::iter::Sum<&'a $name> for $name {
+            fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+                iter.fold(Self(0), |acc, &x| acc + x)
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self { Self::ZERO }
+        }
+
         impl $crate::Zero for $name {
-            fn zero() -> Self { Self(0) }
+            fn zero() -> Self { Self::ZERO }
 
             fn is_zero(&self) -> bool { self.0 == 0 }
         }
 
         impl $crate::One for $name {
-            fn one() -> Self { Self(1) }
+            fn one() -> Self { Self(1 % $mod) }
+        }
+
+        /// The multiplicative inverse through the `num-traits` interface, for generic code written
+        /// against `Zero`, `One`, `Pow` and `Inv`.
+        ///
+        /// The result is `None` if the value has no inverse, as for `Modular::inv`. Call this as
+        /// `Inv::inv(x)` if both traits are in scope.
+        impl $crate::Inv for $name {
+            type Output = Option<Self>;
+
+            fn inv(self) -> Option<Self> { <Self as $crate::Modular<$int>>::inv(self) }
+        }
+
+        /// Raises the value to a signed power, where `self^(-n)` is `(self^-1)^n`.
+        ///
+        /// The result is `None` if the exponent is negative and the value has no inverse. Since
+        /// `Modular::pow` has the same name, call this as `Pow::pow(x, n)` if both traits are in
+        /// scope.
+        impl $crate::Pow<i64> for $name {
+            type Output = Option<Self>;
+
+            fn pow(self, n: i64) -> Option<Self> {
+                <Self as $crate::Modular<$int>>::checked_pow_signed(self, n as i128)
+            }
         }
+
+        /// The bounds of the representative, i.e. `0` and `$mod - 1`.
+        impl $crate::Bounded for $name {
+            fn min_value() -> Self { Self(0) }
+
+            fn max_value() -> Self { Self($mod - 1) }
+        }
+
+        $crate::__impl_field!($name, $int);
     };
 }
@@ -0,0 +1,112 @@
+/*
+ * indent-stack
+ *
+ * Copyright (C) 2019 chankyin
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Discrete logarithms in the cyclic subgroup generated by a unit.
+
+use alloc::vec::Vec;
+
+use crate::{crt_lift, pow_binary, One, WideModular};
+
+/// Resolves the cases that do not involve two units: `target == 1` is reached at `k = 0`, and
+/// zero is reached only from a zero base, at `k = 1`.
+///
+/// Returns `None` if both values are units, so that the caller has to search.
+fn trivial_log<M: WideModular + One>(base: M, target: M) -> Option<Option<u128>> {
+    if target == M::one() {
+        return Some(Some(0));
+    }
+    match (base.remainder_u128(), target.remainder_u128()) {
+        (0, 0) => Some(Some(1)),
+        (0, _) | (_, 0) => Some(None),
+        _ => None,
+    }
+}
+
+/// Finds the least `k >= 0` with `base^k == target` with the baby-step giant-step algorithm,
+/// where `order` is the multiplicative order of `base`.
+///
+/// This takes `O(sqrt(order))` time and memory.
+#[doc(hidden)]
+pub fn discrete_log_bsgs<M: WideModular + One>(base: M, target: M, order: u128) -> Option<u128> {
+    if let Some(k) = trivial_log(base, target) {
+        return k;
+    }
+    let m = order.isqrt() + 1;
+    let mut baby = Vec::with_capacity(m as usize);
+    let mut power = M::one();
+    for j in 0..m {
+        baby.push((power.remainder_u128(), j));
+        power = power * base;
+    }
+    // stable, so the least exponent of each value comes first
+    baby.sort_by_key(|&(value, _)| value);
+    baby.dedup_by_key(|&mut (value, _)| value);
+
+    let giant = pow_binary(base, (order - m % order) % order);
+    let mut gamma = target;
+    for i in 0..m {
+        if let Ok(index) = baby.binary_search_by_key(&gamma.remainder_u128(), |&(value, _)| value) {
+            return Some(i * m + baby[index].1);
+        }
+        gamma = gamma * giant;
+    }
+    None
+}
+
+/// Finds the least `k >= 0` with `base^k == target` with the Pohlig-Hellman algorithm, where
+/// `order` is the multiplicative order of `base` and every prime factor of `order` is in
+/// `factors`.
+///
+/// For each prime power `q^e` exactly dividing `order`, the logarithm modulo `q^e` is found digit
+/// by digit with baby-step giant-step in the subgroup of order `q`, and the results are combined
+/// with the Chinese remainder theorem. This takes `O(sum(e * sqrt(q)))` group operations up to
+/// logarithmic factors.
+#[doc(hidden)]
+pub fn discrete_log_pohlig_hellman<M: WideModular + One>(base: M, target: M, order: u128, factors: &[u128]) -> Option<u128> {
+    if let Some(k) = trivial_log(base, target) {
+        return k;
+    }
+    let mut residues = Vec::new();
+    for &q in factors.iter().filter(|&&q| order.is_multiple_of(q)) {
+        let mut q_e = q;
+        while let Some(next) = q_e.checked_mul(q).filter(|&next| order.is_multiple_of(next)) {
+            q_e = next;
+        }
+        let cofactor = order / q_e;
+        let g = pow_binary(base, cofactor);
+        let h = pow_binary(target, cofactor);
+        let g_inv = pow_binary(g, q_e - 1);
+        let gamma = pow_binary(g, q_e / q);
+
+        // x is the logarithm of h modulo q^k, and q^(e - 1 - k) is `shift`
+        let (mut x, mut q_k, mut shift) = (0, 1, q_e / q);
+        while q_k < q_e {
+            let h_k = pow_binary(pow_binary(g_inv, x) * h, shift);
+            x += discrete_log_bsgs(gamma, h_k, q)? * q_k;
+            q_k *= q;
+            shift /= q;
+        }
+        residues.push((x, q_e));
+    }
+    let (k, _) = crt_lift(&residues)?;
+    if pow_binary(base, k) == target {
+        Some(k)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,83 @@
+/*
+ * indent-stack
+ *
+ * Copyright (C) 2019 chankyin
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#[cfg(feature = "field")]
+use core::fmt::Debug;
+#[cfg(feature = "field")]
+use core::ops::{Add, Mul, Neg, Sub};
+
+/// A field interface in the style of the `ff` crate, so that algorithms written against a
+/// generic field can run on the types generated by [`def_modular!`](macro.def_modular.html).
+///
+/// Every generated type implements this trait, but the field axioms only hold if the modulus is
+/// prime, i.e. if `Modular::IS_FIELD` is true. For composite moduli, `invert` returns `None` for
+/// nonzero values without an inverse.
+///
+/// The method names overlap with `Zero`, `One` and `Modular`, so call them as `Field::zero()`
+/// etc. if several of the traits are in scope.
+///
+/// This is only available with the `field` feature.
+#[cfg(feature = "field")]
+pub trait Field:
+Copy + Debug + Eq + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Neg<Output = Self> {
+    /// The additive identity.
+    fn zero() -> Self;
+
+    /// The multiplicative identity.
+    fn one() -> Self;
+
+    /// Whether the value is the additive identity.
+    fn is_zero(&self) -> bool { *self == Self::zero() }
+
+    /// Squares the value.
+    fn square(&self) -> Self { *self * *self }
+
+    /// Calculates the multiplicative inverse, which is `None` for zero.
+    fn invert(&self) -> Option<Self>;
+
+    /// Raises the value to the `exp`th power.
+    fn pow(&self, exp: u128) -> Self;
+}
+
+/// Implements `Field` for a type generated by `def_modular!`.
+///
+/// This is selected here rather than in `def_modular!`, which would check the features of the
+/// calling crate.
+#[cfg(feature = "field")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_field {
+    ($name:ident, $int:ty) => {
+        impl $crate::Field for $name {
+            fn zero() -> Self { <Self as $crate::Zero>::zero() }
+
+            fn one() -> Self { <Self as $crate::One>::one() }
+
+            fn invert(&self) -> Option<Self> { <Self as $crate::Modular<$int>>::inv(*self) }
+
+            fn pow(&self, exp: u128) -> Self { <Self as $crate::Modular<$int>>::pow_u128(*self, exp) }
+        }
+    };
+}
+
+#[cfg(not(feature = "field"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_field {
+    ($name:ident, $int:ty) => {};
+}
@@ -0,0 +1,105 @@
+/*
+ * indent-stack
+ *
+ * Copyright (C) 2019 chankyin
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use alloc::vec::Vec;
+use core::ops::{Div, Mul, Sub};
+
+use crate::Zero;
+
+/// A reduced basis of vectors over a prime field, the analogue of the binary XOR basis.
+///
+/// The basis is kept in reduced row echelon form: each basis vector has a pivot coordinate equal
+/// to one, and all other basis vectors are zero at that coordinate.
+///
+/// `M` should be a modular type with a prime modulus, since pivots are normalized by division.
+#[derive(Clone, Debug)]
+pub struct LinearBasis<M> {
+    dim: usize,
+    rows: Vec<(usize, Vec<M>)>,
+}
+
+impl<M> LinearBasis<M>
+where M: Copy + Eq + Zero + Sub<Output = M> + Mul<Output = M> + Div<Output = M> {
+    /// Creates an empty basis for vectors of length `dim`.
+    pub fn new(dim: usize) -> Self { Self { dim, rows: Vec::new() } }
+
+    /// The length of the vectors in this basis.
+    pub fn dim(&self) -> usize { self.dim }
+
+    /// The number of linearly independent vectors inserted so far.
+    pub fn rank(&self) -> usize { self.rows.len() }
+
+    /// Reduces the vector against the basis.
+    ///
+    /// The result is zero if and only if the vector is in the span of the basis.
+    ///
+    /// # Panics
+    /// The function panics if the vector length is not `dim`.
+    pub fn reduce(&self, vec: &[M]) -> Vec<M> {
+        assert_eq!(vec.len(), self.dim, "Vector length must match the basis dimension");
+        let mut vec = vec.to_vec();
+        for (pivot, row) in &self.rows {
+            let factor = vec[*pivot];
+            if !factor.is_zero() {
+                for (x, &r) in vec.iter_mut().zip(row) {
+                    *x = *x - factor * r;
+                }
+            }
+        }
+        vec
+    }
+
+    /// Checks whether the vector is in the span of the basis.
+    ///
+    /// # Panics
+    /// The function panics if the vector length is not `dim`.
+    pub fn contains(&self, vec: &[M]) -> bool { self.reduce(vec).iter().all(Zero::is_zero) }
+
+    /// Inserts a vector into the basis.
+    ///
+    /// # Returns
+    /// Returns `true` if the vector was linearly independent of the basis, increasing the rank.
+    ///
+    /// # Panics
+    /// The function panics if the vector length is not `dim`.
+    pub fn insert(&mut self, vec: &[M]) -> bool {
+        let mut vec = self.reduce(vec);
+        let pivot = match vec.iter().position(|x| !x.is_zero()) {
+            Some(pivot) => pivot,
+            None => return false,
+        };
+
+        let scale = vec[pivot];
+        for x in &mut vec {
+            *x = *x / scale;
+        }
+        for (_, row) in &mut self.rows {
+            let factor = row[pivot];
+            if !factor.is_zero() {
+                for (r, &x) in row.iter_mut().zip(&vec) {
+                    *r = *r - factor * x;
+                }
+            }
+        }
+        self.rows.push((pivot, vec));
+        true
+    }
+
+    /// The basis vectors, each normalized to one at its pivot coordinate.
+    pub fn vectors(&self) -> impl Iterator<Item = &[M]> { self.rows.iter().map(|(_, row)| &row[..]) }
+}
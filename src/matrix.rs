@@ -0,0 +1,93 @@
+/*
+ * indent-stack
+ *
+ * Copyright (C) 2019 chankyin
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use alloc::vec::Vec;
+use core::ops::{Add, Index, Mul};
+
+use crate::Zero;
+
+/// A dense matrix of modular values, stored in row-major order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Matrix<M> {
+    rows: usize,
+    cols: usize,
+    data: Vec<M>,
+}
+
+impl<M> Matrix<M>
+where M: Copy + Zero + Add<Output = M> + Mul<Output = M> {
+    /// Creates a `rows` by `cols` matrix whose entry at `(i, j)` is `f(i, j)`.
+    pub fn from_fn(rows: usize, cols: usize, mut f: impl FnMut(usize, usize) -> M) -> Self {
+        let mut data = Vec::with_capacity(rows * cols);
+        for i in 0..rows {
+            for j in 0..cols {
+                data.push(f(i, j));
+            }
+        }
+        Self { rows, cols, data }
+    }
+
+    /// The number of rows.
+    pub fn rows(&self) -> usize { self.rows }
+
+    /// The number of columns.
+    pub fn cols(&self) -> usize { self.cols }
+
+    /// The entries of row `i`.
+    ///
+    /// # Panics
+    /// The function panics if `i >= rows`.
+    pub fn row(&self, i: usize) -> &[M] { &self.data[i * self.cols..(i + 1) * self.cols] }
+
+    /// Multiplies the matrix by a column vector.
+    ///
+    /// # Panics
+    /// The function panics if the vector length is not `cols`.
+    pub fn mul_vec(&self, vec: &[M]) -> Vec<M> {
+        assert_eq!(vec.len(), self.cols, "Vector length must match the number of columns");
+        (0..self.rows)
+            .map(|i| self.row(i).iter().zip(vec).fold(M::zero(), |acc, (&a, &b)| acc + a * b))
+            .collect()
+    }
+}
+
+impl<M> Index<(usize, usize)> for Matrix<M> {
+    type Output = M;
+
+    fn index(&self, (i, j): (usize, usize)) -> &M {
+        assert!(i < self.rows && j < self.cols, "Index ({}, {}) out of bounds for {}x{} matrix", i, j, self.rows, self.cols);
+        &self.data[i * self.cols + j]
+    }
+}
+
+/// Multiplies two matrices.
+///
+/// # Panics
+/// The function panics if the number of columns of the left operand is not the number of rows of
+/// the right operand.
+impl<M> Mul for &Matrix<M>
+where M: Copy + Zero + Add<Output = M> + Mul<Output = M> {
+    type Output = Matrix<M>;
+
+    fn mul(self, rhs: Self) -> Matrix<M> {
+        assert_eq!(self.cols, rhs.rows, "Matrix dimensions do not match for multiplication");
+        Matrix::from_fn(self.rows, rhs.cols, |i, j| {
+            (0..self.cols).fold(M::zero(), |acc, k| acc + self[(i, k)] * rhs[(k, j)])
+        })
+    }
+}
@@ -0,0 +1,153 @@
+/*
+ * indent-stack
+ *
+ * Copyright (C) 2019 chankyin
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Memoization of exponentiation, inversion and factorials, available with the `std` feature.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{PoisonError, RwLock};
+use std::vec::Vec;
+
+use crate::WideModular;
+
+type InvCacheEntry = ((u128, u128), Option<u128>);
+
+std::thread_local! {
+    /// Maps `(modulus, base, exponent)` to the representative of the power.
+    static POW_CACHE: RefCell<HashMap<(u128, u128, u128), u128>> = RefCell::new(HashMap::new());
+
+    /// Maps `(modulus, value)` to the representative of the inverse, most recently used first.
+    static INV_CACHE: RefCell<Vec<InvCacheEntry>> = RefCell::new(Vec::with_capacity(INV_CACHE_CAPACITY));
+}
+
+pub(crate) fn memoized_pow<M: WideModular>(base: M, n: u128) -> M {
+    let key = (M::MOD_U128, base.remainder_u128(), n);
+    if let Some(result) = POW_CACHE.with(|cache| cache.borrow().get(&key).copied()) {
+        return M::from_u128_reduced(result);
+    }
+
+    let mut result = M::from_u128_reduced(1);
+    let mut power = base;
+    let mut exp = n;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * power;
+        }
+        power = power * power;
+        exp >>= 1;
+    }
+    POW_CACHE.with(|cache| cache.borrow_mut().insert(key, result.remainder_u128()));
+    result
+}
+
+/// Clears the cache of [`WideModular::memoized_pow`](trait.WideModular.html#method.memoized_pow)
+/// for the current thread, releasing its memory.
+pub fn clear_memoized_pow_cache() { POW_CACHE.with(|cache| cache.borrow_mut().clear()); }
+
+/// The number of powers cached by
+/// [`WideModular::memoized_pow`](trait.WideModular.html#method.memoized_pow) for the current
+/// thread.
+pub fn memoized_pow_cache_len() -> usize { POW_CACHE.with(|cache| cache.borrow().len()) }
+
+/// The number of inverses remembered per thread by
+/// [`Modular::cached_inv`](trait.Modular.html#method.cached_inv).
+pub const INV_CACHE_CAPACITY: usize = 16;
+
+pub(crate) fn cached_inv<M: WideModular>(value: M, inv: impl FnOnce(M) -> Option<M>) -> Option<M> {
+    let key = (M::MOD_U128, value.remainder_u128());
+    let hit = INV_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let index = cache.iter().position(|&(k, _)| k == key)?;
+        let entry = cache.remove(index);
+        cache.insert(0, entry);
+        Some(entry.1)
+    });
+    if let Some(result) = hit {
+        return result.map(M::from_u128_reduced);
+    }
+
+    let result = inv(value);
+    INV_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        cache.truncate(INV_CACHE_CAPACITY - 1);
+        cache.insert(0, (key, result.map(WideModular::remainder_u128)));
+    });
+    result
+}
+
+/// Factorials `0!, 1!, ...` and their inverses modulo one modulus, as representatives.
+#[derive(Default)]
+struct FactorialTable {
+    fact: Vec<u128>,
+    inv_fact: Vec<u128>,
+}
+
+lazy_static::lazy_static! {
+    /// Maps the modulus to its factorial table, shared by all threads. Tables only grow, and
+    /// always by a complete range, so a poisoned lock still holds consistent tables.
+    static ref FACTORIALS: RwLock<HashMap<u128, FactorialTable>> = RwLock::new(HashMap::new());
+}
+
+/// Grows the shared table of `M::MOD_U128` to cover `n!`, returning `false` if `n!` has no
+/// inverse.
+fn ensure_factorials<M: WideModular>(n: usize, inv: impl FnOnce(M) -> Option<M>) -> bool {
+    let covered = |tables: &HashMap<u128, FactorialTable>| tables.get(&M::MOD_U128).is_some_and(|t| t.fact.len() > n);
+    if covered(&FACTORIALS.read().unwrap_or_else(PoisonError::into_inner)) {
+        return true;
+    }
+    let mut tables = FACTORIALS.write().unwrap_or_else(PoisonError::into_inner);
+    let table = tables.entry(M::MOD_U128).or_default();
+    let start = table.fact.len();
+    if start > n {
+        return true;
+    }
+
+    let mut fact = table.fact.last().map_or(M::from_u128_reduced(1), |&f| M::from_u128_reduced(f));
+    let mut new_fact = Vec::with_capacity(n + 1 - start);
+    for i in start..=n {
+        if i > 0 {
+            fact = fact * M::from_u128_reduced(i as u128);
+        }
+        new_fact.push(fact.remainder_u128());
+    }
+    let mut inv_fact = match inv(fact) {
+        Some(inv_fact) => inv_fact,
+        None => return false,
+    };
+    let mut new_inv_fact = std::vec![0; new_fact.len()];
+    for (offset, slot) in new_inv_fact.iter_mut().enumerate().rev() {
+        *slot = inv_fact.remainder_u128();
+        // 1 / (i - 1)! = i / i!
+        inv_fact = inv_fact * M::from_u128_reduced((start + offset) as u128);
+    }
+    table.fact.extend(new_fact);
+    table.inv_fact.extend(new_inv_fact);
+    true
+}
+
+/// Runs `f` on the shared factorials and inverse factorials of `M::MOD_U128`, which cover at
+/// least `0..=n`, or returns `None` if `n!` has no inverse.
+pub(crate) fn with_factorials<M: WideModular, R>(n: usize, inv: impl FnOnce(M) -> Option<M>,
+                                                 f: impl FnOnce(&[u128], &[u128]) -> R) -> Option<R> {
+    if !ensure_factorials(n, inv) {
+        return None;
+    }
+    let tables = FACTORIALS.read().unwrap_or_else(PoisonError::into_inner);
+    let table = &tables[&M::MOD_U128];
+    Some(f(&table.fact, &table.inv_fact))
+}
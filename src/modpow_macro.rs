@@ -0,0 +1,60 @@
+/*
+ * indent-stack
+ *
+ * Copyright (C) 2019 chankyin
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/// This macro raises a value to a fixed exponent known at compile time.
+///
+/// For literal exponents from `0` to `16`, the macro expands to a shortest addition chain of
+/// multiplications, so hot code with fixed small exponents does not run the generic
+/// exponentiation loop. Other exponents fall back to square-and-multiply.
+///
+/// The value can be of any type implementing `Copy`, `Mul<Output = Self>` and `One`, including
+/// all types generated by [`def_modular`](macro.def_modular.html).
+///
+/// # Example
+/// ```ignore
+/// let cube = modpow!(x, 3); // expands to `x * x * x`
+/// ```
+#[macro_export]
+macro_rules! modpow {
+    ($x:expr, 0) => {{ $crate::pow_binary($x, 0) }};
+    ($x:expr, 1) => {{ $x }};
+    ($x:expr, 2) => {{ let x1 = $x; x1 * x1 }};
+    ($x:expr, 3) => {{ let x1 = $x; let x2 = x1 * x1; x2 * x1 }};
+    ($x:expr, 4) => {{ let x1 = $x; let x2 = x1 * x1; x2 * x2 }};
+    ($x:expr, 5) => {{ let x1 = $x; let x2 = x1 * x1; let x4 = x2 * x2; x4 * x1 }};
+    ($x:expr, 6) => {{ let x1 = $x; let x2 = x1 * x1; let x3 = x2 * x1; x3 * x3 }};
+    ($x:expr, 7) => {{ let x1 = $x; let x2 = x1 * x1; let x3 = x2 * x1; let x5 = x3 * x2; x5 * x2 }};
+    ($x:expr, 8) => {{ let x1 = $x; let x2 = x1 * x1; let x4 = x2 * x2; x4 * x4 }};
+    ($x:expr, 9) => {{ let x1 = $x; let x2 = x1 * x1; let x4 = x2 * x2; let x8 = x4 * x4; x8 * x1 }};
+    ($x:expr, 10) => {{ let x1 = $x; let x2 = x1 * x1; let x4 = x2 * x2; let x5 = x4 * x1; x5 * x5 }};
+    ($x:expr, 11) => {{
+        let x1 = $x; let x2 = x1 * x1; let x3 = x2 * x1; let x5 = x3 * x2; let x10 = x5 * x5; x10 * x1
+    }};
+    ($x:expr, 12) => {{ let x1 = $x; let x2 = x1 * x1; let x3 = x2 * x1; let x6 = x3 * x3; x6 * x6 }};
+    ($x:expr, 13) => {{
+        let x1 = $x; let x2 = x1 * x1; let x3 = x2 * x1; let x5 = x3 * x2; let x10 = x5 * x5; x10 * x3
+    }};
+    ($x:expr, 14) => {{
+        let x1 = $x; let x2 = x1 * x1; let x3 = x2 * x1; let x5 = x3 * x2; let x7 = x5 * x2; x7 * x7
+    }};
+    ($x:expr, 15) => {{
+        let x1 = $x; let x2 = x1 * x1; let x3 = x2 * x1; let x5 = x3 * x2; let x10 = x5 * x5; x10 * x5
+    }};
+    ($x:expr, 16) => {{ let x1 = $x; let x2 = x1 * x1; let x4 = x2 * x2; let x8 = x4 * x4; x8 * x8 }};
+    ($x:expr, $n:expr) => {{ $crate::pow_binary($x, $n as u128) }};
+}
@@ -0,0 +1,111 @@
+/*
+ * indent-stack
+ *
+ * Copyright (C) 2019 chankyin
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use core::marker::PhantomData;
+use core::ops::{Add, Mul, Sub};
+
+use crate::WideModular;
+
+/// A modular value stored in Montgomery form, i.e. as `x * 2^64 mod MOD`.
+///
+/// Multiplication in Montgomery form replaces the division by `MOD` with shifts and
+/// multiplications, which pays off in long chains of multiplications such as exponentiation.
+/// Convert into and out of the form with [`from_modular`](#method.from_modular) and
+/// [`to_modular`](#method.to_modular), or in bulk with
+/// [`WideModular::to_montgomery_slice`](trait.WideModular.html#method.to_montgomery_slice).
+///
+/// The modulus of `M` must be odd and less than `2^64`; using another modulus fails to compile.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Montgomery<M> {
+    value: u64,
+    _marker: PhantomData<M>,
+}
+
+impl<M: WideModular> Montgomery<M> {
+    const VALID_MODULUS: () = assert!(
+        M::MOD_U128 % 2 == 1 && M::MOD_U128 < 1 << 64,
+        "Montgomery form requires an odd modulus less than 2^64"
+    );
+
+    /// `-MOD^-1 mod 2^64`, by Newton's iteration starting from 3 correct bits.
+    const NEG_INV: u64 = {
+        let n = M::MOD_U128 as u64;
+        let mut x = n;
+        let mut i = 0;
+        while i < 5 {
+            x = x.wrapping_mul(2_u64.wrapping_sub(n.wrapping_mul(x)));
+            i += 1;
+        }
+        x.wrapping_neg()
+    };
+
+    /// `2^128 mod MOD`, which converts a value into Montgomery form with one reduction.
+    const R2: u128 = (u128::MAX % M::MOD_U128 + 1) % M::MOD_U128;
+
+    fn from_raw(value: u64) -> Self { Montgomery { value, _marker: PhantomData } }
+
+    /// Montgomery reduction: computes `t / 2^64 mod MOD` for `t < MOD * 2^64`.
+    fn redc(t: u128) -> u64 {
+        let m = (t as u64).wrapping_mul(Self::NEG_INV);
+        let (sum, carry) = t.overflowing_add(m as u128 * M::MOD_U128);
+        // t + m * MOD is divisible by 2^64, and the quotient is less than 2 * MOD
+        let u = (sum >> 64) | ((carry as u128) << 64);
+        if u >= M::MOD_U128 {
+            (u - M::MOD_U128) as u64
+        } else {
+            u as u64
+        }
+    }
+
+    /// Converts a modular value into Montgomery form.
+    pub fn from_modular(x: M) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID_MODULUS;
+        Self::from_raw(Self::redc(x.remainder_u128() * Self::R2))
+    }
+
+    /// Converts the value out of Montgomery form.
+    pub fn to_modular(self) -> M { M::from_u128_reduced(Self::redc(self.value as u128) as u128) }
+}
+
+impl<M: WideModular> Add for Montgomery<M> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let sum = self.value as u128 + rhs.value as u128;
+        Self::from_raw((sum % M::MOD_U128) as u64)
+    }
+}
+
+impl<M: WideModular> Sub for Montgomery<M> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        if self.value >= rhs.value {
+            Self::from_raw(self.value - rhs.value)
+        } else {
+            Self::from_raw((M::MOD_U128 - (rhs.value - self.value) as u128) as u64)
+        }
+    }
+}
+
+impl<M: WideModular> Mul for Montgomery<M> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self { Self::from_raw(Self::redc(self.value as u128 * rhs.value as u128)) }
+}
@@ -0,0 +1,82 @@
+/*
+ * indent-stack
+ *
+ * Copyright (C) 2019 chankyin
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use core::ops::{Add, Mul, Sub};
+
+use crate::{crt_lift, WideModular};
+
+/// Mirrors the same computation across several modular types simultaneously.
+///
+/// `T` is a tuple of 2 to 4 modular types, e.g. `MultiMod<(Mod65521, Mod65519)>`. If the moduli
+/// are pairwise coprime, the exact result of an integer computation can be recovered with
+/// [`reconstruct`](#method.reconstruct) as long as it is less than the product of the moduli.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MultiMod<T>(pub T);
+
+macro_rules! impl_multi_mod {
+    ($($ty:ident $index:tt),+) => {
+        impl<$($ty: WideModular),+> MultiMod<($($ty,)+)> {
+            /// Reduces `value` under each modulus.
+            pub fn new(value: u128) -> Self {
+                MultiMod(($($ty::from_u128_reduced(value),)+))
+            }
+
+            /// The product of all moduli, i.e. the range in which `reconstruct` is exact.
+            ///
+            /// # Panics
+            /// The function panics if the product overflows `u128`.
+            pub fn combined_modulus() -> u128 {
+                let mut product = 1_u128;
+                $(product = product.checked_mul($ty::MOD_U128).expect("Combined modulus overflows u128");)+
+                product
+            }
+
+            /// Reconstructs the unique value in `[0, combined_modulus())` congruent to each residue.
+            ///
+            /// # Panics
+            /// The function panics if the moduli are not pairwise coprime or their product
+            /// overflows `u128`.
+            pub fn reconstruct(self) -> u128 {
+                let residues = [$(((self.0).$index.remainder_u128(), $ty::MOD_U128)),+];
+                crt_lift(&residues).expect("Moduli must be pairwise coprime with a product within u128").0
+            }
+        }
+
+        impl<$($ty: WideModular),+> Add for MultiMod<($($ty,)+)> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self { MultiMod(($((self.0).$index + (rhs.0).$index,)+)) }
+        }
+
+        impl<$($ty: WideModular),+> Sub for MultiMod<($($ty,)+)> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self { MultiMod(($((self.0).$index - (rhs.0).$index,)+)) }
+        }
+
+        impl<$($ty: WideModular),+> Mul for MultiMod<($($ty,)+)> {
+            type Output = Self;
+
+            fn mul(self, rhs: Self) -> Self { MultiMod(($((self.0).$index * (rhs.0).$index,)+)) }
+        }
+    };
+}
+
+impl_multi_mod!(A 0, B 1);
+impl_multi_mod!(A 0, B 1, C 2);
+impl_multi_mod!(A 0, B 1, C 2, D 3);
@@ -0,0 +1,173 @@
+/*
+ * indent-stack
+ *
+ * Copyright (C) 2019 chankyin
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::{Add, Div, Mul, Sub};
+
+use crate::{Modular, One, Zero};
+
+// NTT-friendly primes of the form `k * 2^e + 1` with `e >= 23`, whose product exceeds 2^86
+crate::def_modular!(NttPrime1 : u32 | i32, 998_244_353 ; ntt_prime1_lbl);
+crate::def_modular!(NttPrime2 : u32 | i32, 167_772_161 ; ntt_prime2_lbl);
+crate::def_modular!(NttPrime3 : u32 | i32, 469_762_049 ; ntt_prime3_lbl);
+
+/// The length of the shorter operand below which `convolve` uses the schoolbook algorithm even if
+/// the modulus supports NTT.
+///
+/// Below this length, the three transforms cost more than the `O(nm)` direct product.
+#[doc(hidden)]
+pub const NTT_THRESHOLD: usize = 32;
+
+/// Computes the convolution of `a` and `b` directly in `O(nm)` time.
+#[doc(hidden)]
+pub fn convolve_schoolbook<M>(a: &[M], b: &[M]) -> Vec<M>
+where M: Copy + Zero + Add<Output = M> + Mul<Output = M> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut result = vec![M::zero(); a.len() + b.len() - 1];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            result[i + j] = result[i + j] + x * y;
+        }
+    }
+    result
+}
+
+/// Computes the convolution of `a` and `b` with the number theoretic transform.
+///
+/// `root` must be a primitive `n`th root of unity, where `n` is the smallest power of two not less
+/// than `a.len() + b.len() - 1`.
+#[doc(hidden)]
+pub fn convolve_ntt<M>(a: &[M], b: &[M], root: M) -> Vec<M>
+where M: Copy + Zero + One + Add<Output = M> + Sub<Output = M> + Mul<Output = M> + Div<Output = M> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let len = a.len() + b.len() - 1;
+    let size = len.next_power_of_two();
+
+    let mut fa = a.to_vec();
+    fa.resize(size, M::zero());
+    let mut fb = b.to_vec();
+    fb.resize(size, M::zero());
+    ntt_in_place(&mut fa, root);
+    ntt_in_place(&mut fb, root);
+    for (x, &y) in fa.iter_mut().zip(&fb) {
+        *x = *x * y;
+    }
+    ntt_in_place(&mut fa, M::one() / root);
+
+    let mut size_mod = M::one();
+    let mut bit = size;
+    while bit > 1 {
+        size_mod = size_mod + size_mod;
+        bit >>= 1;
+    }
+    let size_inv = M::one() / size_mod;
+    fa.truncate(len);
+    for x in &mut fa {
+        *x = *x * size_inv;
+    }
+    fa
+}
+
+/// Applies the iterative Cooley-Tukey transform in place, where `root` is a primitive
+/// `values.len()`th root of unity and `values.len()` is a power of two.
+///
+/// The result is in natural order, i.e. `values[k]` becomes `sum(values[j] * root^(jk))`.
+#[doc(hidden)]
+pub fn ntt_in_place<M>(values: &mut [M], root: M)
+where M: Copy + One + Add<Output = M> + Sub<Output = M> + Mul<Output = M> {
+    let n = values.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+
+    // the roots of unity for each stage are the successive squares of `root`, used in reverse
+    let mut stage_roots = Vec::new();
+    let mut w = root;
+    let mut len = n;
+    while len >= 2 {
+        stage_roots.push(w);
+        w = w * w;
+        len >>= 1;
+    }
+
+    let mut len = 2;
+    for &w_len in stage_roots.iter().rev() {
+        let half = len / 2;
+        for chunk in values.chunks_mut(len) {
+            let mut w = M::one();
+            for k in 0..half {
+                let u = chunk[k];
+                let v = chunk[k + half] * w;
+                chunk[k] = u + v;
+                chunk[k + half] = u - v;
+                w = w * w_len;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// Computes the exact convolution of non-negative integers below `bound` by convolving modulo
+/// three NTT-friendly primes and combining the results with Garner's algorithm.
+///
+/// # Returns
+/// Returns `None` if a coefficient of the result may reach the product of the three primes, in
+/// which case it cannot be recovered from its residues.
+#[doc(hidden)]
+pub fn convolve_crt(a: &[u128], b: &[u128], bound: u128) -> Option<Vec<u128>> {
+    let p1 = NttPrime1::MOD as u128;
+    let p2 = NttPrime2::MOD as u128;
+    let p3 = NttPrime3::MOD as u128;
+    let max_coeff = bound.saturating_sub(1).checked_mul(bound.saturating_sub(1))?
+        .checked_mul(a.len().min(b.len()) as u128)?;
+    if max_coeff >= p1 * p2 * p3 {
+        return None;
+    }
+
+    fn residues<M: Modular<u32>>(a: &[u128], b: &[u128], reduce: fn(u128) -> M) -> Vec<M> {
+        let a: Vec<M> = a.iter().map(|&x| reduce(x)).collect();
+        let b: Vec<M> = b.iter().map(|&x| reduce(x)).collect();
+        M::convolve(&a, &b)
+    }
+    let c1 = residues(a, b, NttPrime1::from_u128);
+    let c2 = residues(a, b, NttPrime2::from_u128);
+    let c3 = residues(a, b, NttPrime3::from_u128);
+
+    let p1_inv = NttPrime2::from_u128(p1).inv()?;
+    let p12_inv = NttPrime3::from_u128(p1 * p2).inv()?;
+    Some(c1.iter().zip(&c2).zip(&c3).map(|((x1, x2), x3)| {
+        let x1 = x1.remainder() as u128;
+        let x2 = ((*x2 - NttPrime2::from_u128(x1)) * p1_inv).remainder() as u128;
+        let x3 = ((*x3 - NttPrime3::from_u128(x1 + x2 * p1)) * p12_inv).remainder() as u128;
+        x1 + x2 * p1 + x3 * p1 * p2
+    }).collect())
+}
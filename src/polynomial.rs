@@ -0,0 +1,221 @@
+/*
+ * indent-stack
+ *
+ * Copyright (C) 2019 chankyin
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::{Div, Mul};
+
+use crate::{convolve_schoolbook, One, WideModular, Zero};
+
+/// Moduli up to this bound find polynomial roots by evaluating at every residue, which is cheaper
+/// than the modular exponentiations of the splitting algorithm.
+const ROOT_EVAL_MAX_MOD: u128 = 256;
+
+/// A polynomial with modular coefficients, stored from the constant term upwards.
+///
+/// Trailing zero coefficients are always trimmed, so the zero polynomial has no coefficients and
+/// the derived equality is equality of polynomials.
+///
+/// `M` should be a modular type with a prime modulus for the division-based methods, since
+/// leading coefficients are normalized by division.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Polynomial<M> {
+    coeffs: Vec<M>,
+}
+
+impl<M> Polynomial<M>
+where M: WideModular + Zero + One + Div<Output = M> {
+    /// Creates a polynomial from its coefficients, constant term first.
+    pub fn from_coeffs(mut coeffs: Vec<M>) -> Self {
+        while coeffs.last().is_some_and(Zero::is_zero) {
+            coeffs.pop();
+        }
+        Self { coeffs }
+    }
+
+    /// The coefficients, constant term first, without trailing zeros.
+    pub fn coeffs(&self) -> &[M] { &self.coeffs }
+
+    /// The degree of the polynomial, or `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> { self.coeffs.len().checked_sub(1) }
+
+    /// Checks whether this is the zero polynomial.
+    pub fn is_zero(&self) -> bool { self.coeffs.is_empty() }
+
+    /// Evaluates the polynomial at `x` with Horner's method.
+    pub fn eval(&self, x: M) -> M { self.coeffs.iter().rev().fold(M::zero(), |acc, &c| acc * x + c) }
+
+    /// The formal derivative of the polynomial.
+    pub fn derivative(&self) -> Self {
+        Self::from_coeffs(self.coeffs.iter().enumerate().skip(1)
+            .map(|(i, &c)| c * M::from_u128_reduced(i as u128))
+            .collect())
+    }
+
+    /// Divides the polynomial by its leading coefficient.
+    ///
+    /// The zero polynomial is returned unchanged.
+    pub fn monic(&self) -> Self {
+        match self.coeffs.last() {
+            Some(&lead) => Self { coeffs: self.coeffs.iter().map(|&c| c / lead).collect() },
+            None => self.clone(),
+        }
+    }
+
+    /// Divides the polynomial by `divisor`, returning the quotient and the remainder.
+    ///
+    /// # Panics
+    /// The function panics if `divisor` is zero or its leading coefficient has no inverse.
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        let divisor_degree = divisor.degree().expect("Polynomial division by zero");
+        let lead = divisor.coeffs[divisor_degree];
+        let mut rem = self.coeffs.clone();
+        if rem.len() <= divisor_degree {
+            return (Self::from_coeffs(Vec::new()), self.clone());
+        }
+        let mut quot = vec![M::zero(); rem.len() - divisor_degree];
+        for i in (0..quot.len()).rev() {
+            let factor = rem[i + divisor_degree] / lead;
+            quot[i] = factor;
+            for (j, &d) in divisor.coeffs.iter().enumerate() {
+                rem[i + j] = rem[i + j] - factor * d;
+            }
+        }
+        rem.truncate(divisor_degree);
+        (Self::from_coeffs(quot), Self::from_coeffs(rem))
+    }
+
+    /// The monic greatest common divisor of two polynomials, or zero if both are zero.
+    pub fn gcd(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            let r = a.div_rem(&b).1;
+            a = b;
+            b = r;
+        }
+        a.monic()
+    }
+
+    /// Factors the polynomial into pairwise coprime squarefree factors with Yun's algorithm,
+    /// adapted to the characteristic `MOD`.
+    ///
+    /// Each factor is monic and paired with its multiplicity, in ascending order of multiplicity
+    /// within each power of `MOD`. The product of `factor^multiplicity` is the polynomial divided
+    /// by its leading coefficient; constants, including zero, have no factors.
+    pub fn squarefree_factorization(&self) -> Vec<(Self, usize)> {
+        let mut factors = Vec::new();
+        if self.degree().unwrap_or(0) == 0 {
+            return factors;
+        }
+        let f = self.monic();
+        let mut c = f.gcd(&f.derivative());
+        let mut w = f.div_rem(&c).0;
+        let mut multiplicity = 1;
+        while w.degree() != Some(0) {
+            let y = w.gcd(&c);
+            let factor = w.div_rem(&y).0;
+            if factor.degree() != Some(0) {
+                factors.push((factor, multiplicity));
+            }
+            c = c.div_rem(&y).0;
+            w = y;
+            multiplicity += 1;
+        }
+        if c.degree() != Some(0) {
+            // the remaining part has a zero derivative, so it is a polynomial in x^MOD, and since
+            // a^MOD == a in the prime field, it is the MOD-th power of the polynomial below
+            let p = M::MOD_U128 as usize;
+            let root = Self::from_coeffs(c.coeffs.iter().step_by(p).copied().collect());
+            factors.extend(root.squarefree_factorization().into_iter().map(|(g, m)| (g, m * p)));
+        }
+        factors
+    }
+
+    /// Finds the distinct roots of the polynomial in the prime field, in ascending order of
+    /// representatives.
+    ///
+    /// Small moduli evaluate the polynomial at every residue. Otherwise, the product of the
+    /// linear factors is extracted as `gcd(self, x^MOD - x)` and split by `gcd` with
+    /// `(x + a)^((MOD - 1) / 2) - 1` for `a = 0, 1, 2, ...`, which takes
+    /// `O(d^2 log MOD)` operations per attempt for degree `d`. The zero polynomial is reported
+    /// as having no roots.
+    pub fn roots(&self) -> Vec<M> {
+        if self.is_zero() {
+            return Vec::new();
+        }
+        let mut roots = if M::MOD_U128 <= ROOT_EVAL_MAX_MOD {
+            (0..M::MOD_U128).map(M::from_u128_reduced).filter(|&x| self.eval(x).is_zero()).collect()
+        } else {
+            let f = self.monic();
+            let x = Self::from_coeffs(vec![M::zero(), M::one()]);
+            let mut linear = x.pow_mod(M::MOD_U128, &f).coeffs;
+            linear.resize(linear.len().max(2), M::zero());
+            linear[1] = linear[1] - M::one();
+            let mut roots = Vec::new();
+            Self::split_linear(&f.gcd(&Self::from_coeffs(linear)), &mut roots);
+            roots
+        };
+        roots.sort_unstable_by_key(|x| x.remainder_u128());
+        roots
+    }
+
+    /// Collects the roots of a monic product of distinct linear factors, for an odd prime modulus.
+    fn split_linear(g: &Self, roots: &mut Vec<M>) {
+        match g.degree() {
+            None | Some(0) => return,
+            Some(1) => {
+                roots.push(M::zero() - g.coeffs[0]);
+                return;
+            },
+            Some(_) => {},
+        }
+        for a in 0..M::MOD_U128 {
+            let shifted = Self::from_coeffs(vec![M::from_u128_reduced(a), M::one()]);
+            let mut half = shifted.pow_mod((M::MOD_U128 - 1) / 2, g).coeffs;
+            half.resize(half.len().max(1), M::zero());
+            half[0] = half[0] - M::one();
+            let h = g.gcd(&Self::from_coeffs(half));
+            if h.degree().is_some_and(|d| d > 0 && Some(d) < g.degree()) {
+                Self::split_linear(&h, roots);
+                Self::split_linear(&g.div_rem(&h).0, roots);
+                return;
+            }
+        }
+    }
+
+    /// Computes `self^exp` modulo `modulus` by square-and-multiply.
+    fn pow_mod(&self, mut exp: u128, modulus: &Self) -> Self {
+        let mut base = self.div_rem(modulus).1;
+        let mut result = Self::from_coeffs(vec![M::one()]).div_rem(modulus).1;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = (&result * &base).div_rem(modulus).1;
+            }
+            base = (&base * &base).div_rem(modulus).1;
+            exp >>= 1;
+        }
+        result
+    }
+}
+
+impl<M> Mul for &Polynomial<M>
+where M: WideModular + Zero + One + Div<Output = M> {
+    type Output = Polynomial<M>;
+
+    fn mul(self, rhs: Self) -> Polynomial<M> { Polynomial::from_coeffs(convolve_schoolbook(&self.coeffs, &rhs.coeffs)) }
+}
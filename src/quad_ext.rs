@@ -0,0 +1,116 @@
+/*
+ * indent-stack
+ *
+ * Copyright (C) 2019 chankyin
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use core::ops::{Add, Mul, Sub};
+
+use crate::{pow_binary, One, WideModular, Zero};
+
+/// An element `re + im * w` of the quadratic extension obtained by adjoining `w` with `w^2 == sq`.
+///
+/// If `sq` is a quadratic non-residue modulo a prime, this is the field `GF(MOD^2)`; otherwise it
+/// is still a ring, as used by Cipolla's algorithm for square roots. Both operands of a binary
+/// operator must use the same `sq`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct QuadExt<M> {
+    re: M,
+    im: M,
+    sq: M,
+}
+
+impl<M> QuadExt<M>
+where M: WideModular + Zero + One {
+    /// Creates the element `re + im * w` where `w^2 == sq`.
+    pub fn new(re: M, im: M, sq: M) -> Self { QuadExt { re, im, sq } }
+
+    /// Embeds a base element into the extension where `w^2 == sq`.
+    pub fn from_base(re: M, sq: M) -> Self { Self::new(re, M::zero(), sq) }
+
+    /// The coefficient of `1`.
+    pub fn re(self) -> M { self.re }
+
+    /// The coefficient of `w`.
+    pub fn im(self) -> M { self.im }
+
+    /// The square of the adjoined element `w`.
+    pub fn sq(self) -> M { self.sq }
+
+    /// The trace `2 * re`, i.e. the sum of the value and its conjugate `re - im * w`.
+    ///
+    /// The trace is additive.
+    pub fn trace(self) -> M { self.re + self.re }
+
+    /// The norm `re^2 - sq * im^2`, i.e. the product of the value and its conjugate
+    /// `re - im * w`.
+    ///
+    /// The norm is multiplicative, and a value is invertible exactly when its norm is.
+    pub fn norm(self) -> M { self.re * self.re - self.sq * self.im * self.im }
+
+    /// The Frobenius map `self^MOD` for an odd prime modulus.
+    ///
+    /// Since `re^MOD == re` and `w^MOD == sq^((MOD - 1) / 2) * w`, this only scales `im` by the
+    /// Legendre symbol of `sq`. In `GF(MOD^2)`, where `sq` is a non-residue, it is the conjugation
+    /// `re - im * w`, so applying it twice gives the identity.
+    pub fn frobenius(self) -> Self {
+        let symbol = pow_binary(self.sq, (M::MOD_U128 - 1) / 2);
+        Self::new(self.re, self.im * symbol, self.sq)
+    }
+
+    /// Raises the value to the `n`th power by square-and-multiply.
+    pub fn pow(self, mut n: u128) -> Self {
+        let mut result = Self::from_base(M::one(), self.sq);
+        let mut base = self;
+        while n > 0 {
+            if n & 1 == 1 {
+                result = result * base;
+            }
+            base = base * base;
+            n >>= 1;
+        }
+        result
+    }
+}
+
+impl<M> Add for QuadExt<M>
+where M: WideModular + Zero + One {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        debug_assert!(self.sq == rhs.sq, "adding elements of different extensions");
+        Self::new(self.re + rhs.re, self.im + rhs.im, self.sq)
+    }
+}
+
+impl<M> Sub for QuadExt<M>
+where M: WideModular + Zero + One {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        debug_assert!(self.sq == rhs.sq, "subtracting elements of different extensions");
+        Self::new(self.re - rhs.re, self.im - rhs.im, self.sq)
+    }
+}
+
+impl<M> Mul for QuadExt<M>
+where M: WideModular + Zero + One {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        debug_assert!(self.sq == rhs.sq, "multiplying elements of different extensions");
+        Self::new(self.re * rhs.re + self.sq * self.im * rhs.im, self.re * rhs.im + self.im * rhs.re, self.sq)
+    }
+}
@@ -0,0 +1,78 @@
+/*
+ * indent-stack
+ *
+ * Copyright (C) 2019 chankyin
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use alloc::collections::VecDeque;
+use core::fmt::Debug;
+use core::ops::{Add, Mul, Rem, Sub};
+
+use crate::{Modular, One};
+
+/// The product of a sliding window of modular values, e.g. for product queries over all windows
+/// of a fixed length.
+///
+/// Values enter at the back and leave at the front. The running product is updated by
+/// multiplying in each new value and dividing out each leaving value. If a leaving value has no
+/// inverse, such as zero, the product is recomputed from the values still in the window instead.
+#[derive(Clone, Debug)]
+pub struct SlidingProduct<M> {
+    window: VecDeque<M>,
+    product: M,
+}
+
+impl<M> SlidingProduct<M>
+where M: Copy + One + Mul<Output = M> {
+    /// Creates an empty window, whose product is one.
+    pub fn new() -> Self { Self { window: VecDeque::new(), product: M::one() } }
+
+    /// The number of values in the window.
+    pub fn len(&self) -> usize { self.window.len() }
+
+    /// Checks whether the window is empty.
+    pub fn is_empty(&self) -> bool { self.window.is_empty() }
+
+    /// The product of all values in the window.
+    pub fn product(&self) -> M { self.product }
+
+    /// Appends a value to the back of the window.
+    pub fn push(&mut self, value: M) {
+        self.window.push_back(value);
+        self.product = self.product * value;
+    }
+
+    /// Removes the value at the front of the window.
+    ///
+    /// This takes constant time and one inversion if the value is a unit, and time linear in the
+    /// window length otherwise.
+    ///
+    /// # Returns
+    /// Returns the removed value, or `None` if the window is empty.
+    pub fn pop<T>(&mut self) -> Option<M>
+    where M: Modular<T>, T: Copy + Debug + Default + Add + Sub + Mul + Rem {
+        let value = self.window.pop_front()?;
+        self.product = match value.inv() {
+            Some(inv) => self.product * inv,
+            None => self.window.iter().fold(M::one(), |acc, &x| acc * x),
+        };
+        Some(value)
+    }
+}
+
+impl<M> Default for SlidingProduct<M>
+where M: Copy + One + Mul<Output = M> {
+    fn default() -> Self { Self::new() }
+}
@@ -16,7 +16,54 @@
  * limitations under the License.
  */
 
+use alloc::boxed::Box;
+use alloc::collections::BinaryHeap;
+use alloc::format;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+use core::convert::TryFrom;
+use core::num::{NonZeroU16, NonZeroU8};
+
+use crate::{Bounded, batch_inv, carmichael, is_primitive_root, mobius, num_divisors, smallest_prime_factor_sieve, Binomials, crt_combine, crt_lift, crt_reconstruct_checked, mod_dot, Annotated, DynModularOps, InvStrategy, LinearBasis, Matrix, ModU64, Modular, ModularError, Montgomery,
+            MultiMod, Polynomial, QuadExt, SlidingProduct, WideModular, Zero};
+
 crate::def_modular!(Mod101 : u16 | i16, 101 ; lbl);
+crate::def_modular!(Mod100 : u16 | i16, 100 ; mod100_lbl);
+crate::def_modular!(Mod103 : u16 | i16, 103 ; mod103_lbl);
+crate::def_modular!(Mod107 : u16 | i16, 107 ; mod107_lbl);
+crate::def_modular!(Mod97 : u16 | i16, 97 ; mod97_lbl);
+crate::def_modular!(Mod15 : u8 | i8, 15 ; mod15_lbl);
+crate::def_modular!(Mod5 : u8 | i8, 5 ; mod5_lbl);
+crate::def_modular!(Mod121 : u16 | i16, 121 ; mod121_lbl);
+// the largest primes below half of the signed maximum, where sums of two values nearly fill it
+crate::def_modular!(Mod16381 : u16 | i16, 16381 ; mod16381_lbl);
+crate::def_modular!(Mod1073741789 : u32 | i32, 1_073_741_789 ; mod1073741789_lbl);
+crate::def_modular!(Mod2p62m57 : u64 | i64, 4_611_686_018_427_387_847 ; mod2p62m57_lbl);
+// the largest modulus allowed for u8 by the overflow check, where products overflow u8
+crate::def_modular!(Mod63 : u8 | i8, 63 ; mod63_lbl);
+// the Mersenne prime 2^89 - 1, where products overflow u128
+crate::def_modular!(Mod2p89 : u128 | i128, 618_970_019_642_690_137_449_562_111 ; mod2p89_lbl);
+crate::def_modular!(Mod65521 : u32 | i32, 65521 ; mod65521_lbl);
+crate::def_modular!(Mod65519 : u32 | i32, 65519 ; mod65519_lbl);
+// 2^126 - 1, the largest modulus allowed for u128 by the overflow check
+crate::def_modular!(ModMaxU128 : u128 | i128, 85_070_591_730_234_615_865_843_651_857_942_052_863 ; mod_max_u128_lbl);
+// the largest prime below 2^64, where Montgomery reduction carries past u128
+crate::def_modular!(Mod2p64m59 : u128 | i128, 18_446_744_073_709_551_557 ; mod2p64m59_lbl);
+// the degenerate modulus, where every value is zero; clippy rightly flags the `% 1` and empty ranges
+#[allow(clippy::modulo_one, clippy::reversed_empty_ranges)]
+mod degenerate {
+    crate::def_modular!(Mod1 : u8 | i8, 1 ; mod1_lbl);
+}
+use degenerate::Mod1;
+// power-of-two moduli, reduced by masking
+crate::def_modular!(Mod256 : u16 | i16, 256 ; mod256_lbl);
+crate::def_modular!(Mod1024 : u16 | i16, 1024 ; mod1024_lbl);
+// the largest prime allowed for u8 by the relaxed overflow check, where sums overflow u8
+#[cfg(feature = "checked")]
+crate::def_modular!(Mod127 : u8 | i8, 127 ; mod127_lbl);
+// the NTT prime 119 * 2^23 + 1
+crate::def_modular!(Mod998244353 : u64 | i64, 998_244_353 ; mod998244353_lbl);
 
 #[test]
 fn test_identical() {
@@ -62,3 +109,2888 @@ fn test_normal_mul() {
 fn test_wrapping_mul() {
     assert_eq!(Mod101::new(100) * Mod101::new(100), Mod101::new(10000));
 }
+
+#[test]
+fn test_rep_is_even() {
+    assert!(Mod101::new(0).rep_is_even());
+    assert!(Mod101::new(42).rep_is_even());
+    assert!(Mod101::new(100).rep_is_even());
+    assert!(!Mod101::new(7).rep_is_even());
+}
+
+#[test]
+fn test_rep_is_odd() {
+    assert!(Mod101::new(7).rep_is_odd());
+    assert!(!Mod101::new(42).rep_is_odd());
+    // -1 is represented by 100, which is even
+    assert!(Mod101::new(-1).rep_is_even());
+    // -2 is represented by 99, which is odd
+    assert!(Mod101::new(-2).rep_is_odd());
+}
+
+#[test]
+fn test_pow_u128() {
+    assert_eq!(Mod101::new(3).pow_u128(0), Mod101::new(1));
+    assert_eq!(Mod101::new(3).pow_u128(4), Mod101::new(81));
+    assert_eq!(Mod101::new(3).pow_u128(5), Mod101::new(243));
+    // Fermat's little theorem
+    assert_eq!(Mod101::new(3).pow_u128(100), Mod101::new(1));
+}
+
+#[test]
+fn test_pow_mod_order_with_coprime() {
+    // φ(100) = 40
+    let exp = 1_000_000_000_000_000_007_u128;
+    assert_eq!(Mod100::new(3).pow_mod_order_with(exp, 40), Mod100::new(3).pow_u128(exp % 40));
+    assert_eq!(Mod100::new(3).pow_mod_order_with(exp, 40), Mod100::new(3).pow_u128(exp));
+    // λ(100) = 20 is also a valid multiple of the order
+    assert_eq!(Mod100::new(7).pow_mod_order_with(exp, 20), Mod100::new(7).pow_u128(exp));
+}
+
+#[test]
+fn test_pow_mod_order_with_non_coprime() {
+    // 2 is not coprime to 100, so the exponent must not be reduced
+    assert_eq!(Mod100::new(2).pow_mod_order_with(40, 40), Mod100::new(2).pow_u128(40));
+    assert_ne!(Mod100::new(2).pow_mod_order_with(40, 40), Mod100::new(1));
+}
+
+#[test]
+#[should_panic(expected = "the totient must be nonzero")]
+fn test_pow_mod_order_with_zero_totient() {
+    Mod100::new(3).pow_mod_order_with(5, 0);
+}
+
+fn generic_dot<M: Modular<u16>>(a: &[M], b: &[M]) -> M { mod_dot(a, b) }
+
+#[test]
+fn test_mod_dot() {
+    let a = [Mod101::new(1), Mod101::new(2), Mod101::new(3)];
+    let b = [Mod101::new(4), Mod101::new(5), Mod101::new(6)];
+    assert_eq!(generic_dot(&a, &b), Mod101::new(32));
+}
+
+#[test]
+fn test_mod_dot_wrapping() {
+    let a = [Mod101::new(100), Mod101::new(50)];
+    let b = [Mod101::new(100), Mod101::new(3)];
+    assert_eq!(mod_dot(&a, &b), Mod101::new(100 * 100 + 50 * 3));
+}
+
+#[test]
+fn test_mod_dot_empty() {
+    let empty: [Mod101; 0] = [];
+    assert_eq!(mod_dot(&empty, &empty), Mod101::new(0));
+}
+
+#[test]
+#[should_panic]
+fn test_mod_dot_length_mismatch() {
+    mod_dot(&[Mod101::new(1)], &[]);
+}
+
+#[test]
+fn test_sum() {
+    let values = [Mod101::new(50), Mod101::new(60), Mod101::new(70)];
+    assert_eq!(values.iter().sum::<Mod101>(), Mod101::new(180));
+    assert_eq!(values.iter().copied().sum::<Mod101>(), Mod101::new(180));
+}
+
+#[test]
+fn test_sum_result_ok() {
+    let values: [Result<Mod101, &str>; 3] = [Ok(Mod101::new(50)), Ok(Mod101::new(60)), Ok(Mod101::new(70))];
+    assert_eq!(values.iter().copied().sum::<Result<Mod101, _>>(), Ok(Mod101::new(180)));
+}
+
+#[test]
+fn test_sum_result_short_circuit() {
+    let values: [Result<Mod101, &str>; 4] = [Ok(Mod101::new(1)), Err("first"), Ok(Mod101::new(2)), Err("second")];
+    let mut visited = 0;
+    let sum = values.iter().copied().inspect(|_| visited += 1).sum::<Result<Mod101, _>>();
+    assert_eq!(sum, Err("first"));
+    assert_eq!(visited, 2);
+}
+
+#[test]
+fn test_inv_prime_matrix() {
+    assert_eq!(Mod101::new(0).inv(), None);
+    assert_eq!(Mod101::new(0).euler_inv(), None);
+    assert_eq!(Mod101::new(0).brute_force_inv(), None);
+    for i in 1..101 {
+        let x = Mod101::from(i as u16);
+        let inv = x.inv().unwrap();
+        assert_eq!(x * inv, Mod101::new(1), "{:?} * {:?} != 1", x, inv);
+        assert_eq!(x.prime_inv(), Some(inv));
+        assert_eq!(x.coprime_inv(), Some(inv));
+        assert_eq!(x.euler_inv(), Some(inv));
+        assert_eq!(x.brute_force_inv(), Some(inv));
+    }
+}
+
+// prime_inv computes self^(MOD - 2), so it is the first thing to break if pow mishandles odd
+// exponents
+#[test]
+fn test_prime_inv_regression() {
+    for i in 1..103_u16 {
+        let x = Mod103::from(i);
+        assert_eq!(x * x.prime_inv_unwrap(), Mod103::new(1), "{:?}", x);
+    }
+    for i in 1..61_u8 {
+        let x = WidthU8::from(i);
+        assert_eq!(x * x.prime_inv_unwrap(), WidthU8::new(1), "{:?}", x);
+    }
+    for i in 1..65521_u32 {
+        let x = Mod65521::from(i);
+        assert_eq!(x * x.prime_inv_unwrap(), Mod65521::new(1), "{:?}", x);
+    }
+    for i in 1..1000_i128 {
+        let x = Mod2p89::new(-i * 1_000_000_007);
+        assert_eq!(x * x.prime_inv_unwrap(), Mod2p89::new(1), "{:?}", x);
+    }
+}
+
+// inv picks one of the two algorithms by the cost of the exponentiation, so both must agree on
+// either side of FERMAT_INV_MAX_COST
+#[test]
+fn test_inv_strategies_agree() {
+    fn check<M, T>()
+    where M: Modular<T> + WideModular, T: Copy + core::fmt::Debug + Default + core::ops::Add + core::ops::Sub + core::ops::Mul + core::ops::Rem {
+        let mut x = M::from_u128_reduced(1);
+        for _ in 0..1000 {
+            x = x * M::from_u128_reduced(3) + M::from_u128_reduced(1);
+            if x.is_zero() {
+                continue;
+            }
+            let inv = x.inv().unwrap();
+            assert_eq!(x.prime_inv(), Some(inv));
+            assert_eq!(x.coprime_inv(), Some(inv));
+            assert_eq!(x * inv, M::one());
+        }
+    }
+    check::<Mod101, _>();
+    check::<Mod65521, _>();
+    check::<Mod998244353, _>();
+    check::<Mod1073741789, _>();
+    check::<Mod2p62m57, _>();
+}
+
+#[test]
+fn test_inv_composite_matrix() {
+    assert_eq!(Mod100::new(0).inv(), None);
+    for i in 1..100 {
+        let x = Mod100::from(i as u16);
+        if i % 2 == 0 || i % 5 == 0 {
+            assert_eq!(x.inv(), None, "{:?} should not be invertible", x);
+            assert_eq!(x.euler_inv(), None);
+            assert_eq!(x.brute_force_inv(), None);
+        } else {
+            let inv = x.inv().unwrap();
+            assert_eq!(x * inv, Mod100::new(1), "{:?} * {:?} != 1", x, inv);
+            assert_eq!(x.coprime_inv(), Some(inv));
+            assert_eq!(x.euler_inv(), Some(inv));
+            assert_eq!(x.brute_force_inv(), Some(inv));
+        }
+    }
+}
+
+#[test]
+fn test_reduce_slice_raw() {
+    let raw = [0_u16, 5, 100, 101, 102, 1000, u16::MAX];
+    let mut reduced = raw;
+    Mod101::reduce_slice_raw(&mut reduced);
+    for (&r, &x) in raw.iter().zip(&reduced) {
+        assert!(x < 101);
+        assert_eq!(Mod101::from(x), Mod101::from(r));
+        assert_eq!(Mod101::from(r).remainder(), x);
+    }
+}
+
+#[test]
+fn test_ord_by_representative() {
+    assert!(Mod101::new(3) < Mod101::new(4));
+    // -1 is represented by 100
+    assert!(Mod101::new(-1) > Mod101::new(99));
+    assert!(Mod101::new(102) < Mod101::new(2 + 1));
+}
+
+#[test]
+fn test_max_heap() {
+    let mut heap: BinaryHeap<Mod101> = [5, -1, 102, 50].iter().map(|&i| Mod101::new(i)).collect();
+    assert_eq!(heap.pop(), Some(Mod101::new(100)));
+    assert_eq!(heap.pop(), Some(Mod101::new(50)));
+    assert_eq!(heap.pop(), Some(Mod101::new(5)));
+    assert_eq!(heap.pop(), Some(Mod101::new(1)));
+    assert_eq!(heap.pop(), None);
+}
+
+#[test]
+fn test_min_heap_reverse() {
+    let mut heap: BinaryHeap<Reverse<Mod101>> = [5, -1, 102, 50].iter().map(|&i| Reverse(Mod101::new(i))).collect();
+    let order: Vec<_> = core::iter::from_fn(|| heap.pop().map(|Reverse(x)| x.remainder())).collect();
+    assert_eq!(order, [1, 5, 50, 100]);
+}
+
+#[test]
+fn test_pow_around_window_threshold() {
+    // composite moduli do not reduce the exponent, so this exercises the windowed path
+    let base = Mod100::new(7);
+    let threshold = crate::POW_WINDOW_THRESHOLD;
+    let mut expected = Mod100::new(1);
+    for n in 0..threshold + 20 {
+        if n + 20 >= threshold {
+            assert_eq!(base.pow_u128(n), expected, "7^{} is incorrect", n);
+            assert_eq!(base.pow(n as usize), expected, "7^{} is incorrect", n);
+        }
+        expected *= base;
+    }
+}
+
+#[test]
+fn test_pow_large_exponent() {
+    // 7 is a unit, so its order divides 100
+    for &n in &[1_u128 << 20, (1 << 64) + 3, u128::MAX] {
+        assert_eq!(Mod101::new(7).pow_u128(n), Mod101::new(7).pow_u128(n % 100));
+    }
+    assert_eq!(Mod101::new(0).pow_u128(u128::MAX), Mod101::new(0));
+}
+
+#[test]
+fn test_is_field() {
+    assert_eq!([Mod101::IS_FIELD, Mod100::IS_FIELD], [true, false]);
+}
+
+#[test]
+fn test_const_is_prime() {
+    for n in (0..2000_u128).chain(1_000_000_000..1_000_010_000) {
+        assert_eq!(crate::const_is_prime(n), crate::is_prime(n) && n >= 2, "{}", n);
+    }
+    assert!(crate::const_is_prime(1_000_000_007));
+    assert!(crate::const_is_prime(998_244_353));
+    assert!(crate::const_is_prime((1 << 61) - 1));
+    assert!(!crate::const_is_prime(1_000_000_007 * 998_244_353));
+    // strong pseudoprime to bases 2, 3, 5 and 7
+    assert!(!crate::const_is_prime(3_215_031_751));
+    // strong pseudoprime to all prime bases up to 37
+    assert!(!crate::const_is_prime(318_665_857_834_031_151_167_461));
+    assert!(!crate::const_is_prime(3_317_044_064_679_887_385_961_981));
+    // strong pseudoprimes to base 2 and strong Lucas pseudoprimes
+    for &n in &[2047_u128, 3277, 4033, 4681, 8321, 5459, 5777, 10877, 16109, 18971, 22499] {
+        assert!(!crate::const_is_prime(n), "{}", n);
+    }
+    assert!(!crate::const_is_prime(1_000_003 * 1_000_003));
+    assert!(crate::const_is_prime(618_970_019_642_690_137_449_562_111));
+    assert!(crate::const_is_prime((1 << 127) - 1));
+    assert!(crate::const_is_prime(u128::MAX - 158));
+    assert!(!crate::const_is_prime(u128::MAX));
+}
+
+crate::def_modular!(Mr12Pseudoprime : u128 | i128, 318_665_857_834_031_151_167_461 ; mr12_pseudoprime_lbl);
+
+// 399165290221 * 798330580441 passes Miller-Rabin for the 12 smallest prime bases
+#[test]
+fn test_mr_pseudoprime_modulus_is_not_field() {
+    assert_eq!([Mod2p89::IS_FIELD, Mr12Pseudoprime::IS_FIELD], [true, false]);
+    let x = Mr12Pseudoprime::from(399_165_290_221_u128);
+    assert_eq!(x.prime_inv(), None);
+    assert_eq!(x.inv(), None);
+    assert_eq!(x.legendre(), None);
+}
+
+#[test]
+fn test_inv_dispatch_field() {
+    for i in 1..101 {
+        let x = Mod101::from(i as u16);
+        assert_eq!(x * x.inv().unwrap(), Mod101::new(1));
+    }
+    assert_eq!(Mod100::new(3).inv(), Some(Mod100::new(67)));
+    assert_eq!(Mod100::new(4).inv(), None);
+}
+
+#[test]
+fn test_sub_exhaustive_u8() {
+    for a in 0..15_u8 {
+        for b in 0..15_u8 {
+            let expected = (a as i16 - b as i16).rem_euclid(15) as u8;
+            assert_eq!((Mod15::from(a) - Mod15::from(b)).remainder(), expected, "{} - {}", a, b);
+        }
+    }
+}
+
+#[test]
+fn test_sub_zero_difference() {
+    assert_eq!(Mod15::new(14) - Mod15::new(14), Mod15::new(0));
+    assert_eq!(Mod15::new(0) - Mod15::new(14), Mod15::new(1));
+}
+
+const TABLE: [Mod101; 4] = [
+    Mod101::from_signed(-1),
+    Mod101::from_unsigned(205),
+    Mod101::from_u128(u128::MAX),
+    Mod101::from_u128(101),
+];
+
+#[test]
+fn test_const_table() {
+    assert_eq!(TABLE[0], Mod101::new(100));
+    assert_eq!(TABLE[1], Mod101::new(3));
+    assert_eq!(TABLE[2], Mod101::from((u128::MAX % 101) as u16));
+    assert_eq!(TABLE[3], Mod101::new(0));
+}
+
+#[test]
+fn test_const_constructors_match_from() {
+    for i in -300..300_i16 {
+        assert_eq!(Mod101::from_signed(i), Mod101::from(i));
+    }
+    for i in 0..300_u16 {
+        assert_eq!(Mod101::from_unsigned(i), Mod101::from(i));
+        assert_eq!(Mod101::from_u128(i as u128), Mod101::from(i));
+    }
+}
+
+#[test]
+fn test_display_bare() {
+    assert_eq!(format!("{}", Mod101::new(-1)), "100");
+    assert_eq!(format!("{:>4}", Mod101::new(5)), "   5");
+}
+
+#[test]
+fn test_display_annotated() {
+    assert_eq!(format!("{}", Annotated::new(Mod101::new(-1))), "100 (mod 101)");
+    assert_eq!(format!("{}", Annotated::new(Mod15::new(20))), "5 (mod 15)");
+    assert_eq!(Annotated::new(Mod101::new(7)).into_inner(), Mod101::new(7));
+}
+
+#[test]
+fn test_batch_inv() {
+    let values: Vec<_> = (1..101).map(|i: u16| Mod101::from(i)).collect();
+    let inverses = batch_inv(&values).unwrap();
+    for (&x, &inv) in values.iter().zip(&inverses) {
+        assert_eq!(Some(inv), x.inv());
+    }
+    assert_eq!(batch_inv::<Mod101, _>(&[]), Some(Vec::new()));
+    assert_eq!(batch_inv(&[Mod101::new(3), Mod101::new(0)]), None);
+    assert_eq!(batch_inv(&[Mod100::new(3), Mod100::new(10)]), None);
+}
+
+#[test]
+fn test_crt_lift() {
+    assert_eq!(crt_lift(&[]), Some((0, 1)));
+    assert_eq!(crt_lift(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+    assert_eq!(crt_lift(&[(1, 4), (3, 6)]), None);
+    assert_eq!(crt_lift(&[(0, 1 << 64), (0, 1 << 64)]), None);
+    let big = (1_u128 << 61) - 1;
+    assert_eq!(crt_lift(&[(5, big), (7, 1_000_000_007)]).map(|(v, m)| (v % big, v % 1_000_000_007, m)),
+               Some((5, 7, big * 1_000_000_007)));
+}
+
+/// Computes C(30, 15) = 30! / (15! 15!) under two moduli with batch-inverted denominators, then
+/// reconstructs the exact integer with the Chinese Remainder Theorem.
+#[test]
+fn test_crt_batch_inv_binomial() {
+    fn binomial_30_15<M: Modular<u32>>() -> M {
+        let numerators: Vec<M> = (16..=30).map(M::from).collect();
+        let denominators: Vec<M> = (1..=15).map(M::from).collect();
+        let inverses = batch_inv(&denominators).unwrap();
+        numerators.iter().zip(&inverses).fold(M::one(), |acc, (&n, &d)| acc * n * d)
+    }
+
+    let a: Mod65521 = binomial_30_15();
+    let b: Mod65519 = binomial_30_15();
+    let (value, modulus) = crt_lift(&[
+        (a.remainder() as u128, Mod65521::MOD as u128),
+        (b.remainder() as u128, Mod65519::MOD as u128),
+    ]).unwrap();
+    assert_eq!(modulus, 65521 * 65519);
+    assert_eq!(value, 155_117_520);
+}
+
+#[test]
+fn test_multi_mod_arithmetic() {
+    type Pair = MultiMod<(Mod65521, Mod65519)>;
+    assert_eq!(Pair::combined_modulus(), 65521 * 65519);
+    let a = Pair::new(12_345);
+    let b = Pair::new(6_789);
+    assert_eq!((a + b).reconstruct(), 12_345 + 6_789);
+    assert_eq!((a - b).reconstruct(), 12_345 - 6_789);
+    assert_eq!((a * b).reconstruct(), 12_345 * 6_789);
+    assert_eq!((a * a - b * b).reconstruct(), 12_345 * 12_345 - 6_789 * 6_789);
+}
+
+#[test]
+fn test_multi_mod_triple() {
+    type Triple = MultiMod<(Mod65521, Mod65519, Mod101)>;
+    let x = Triple::new(1 << 36);
+    assert_eq!((x + Triple::new(5)).reconstruct(), (1 << 36) + 5);
+    // beyond the combined modulus, the result wraps around
+    let m = Triple::combined_modulus();
+    assert_eq!((x * x).reconstruct(), (1_u128 << 72) % m);
+}
+
+#[test]
+fn test_from_digits() {
+    assert_eq!(Mod101::from_digits(&[1, 2, 3], 10), Mod101::new(123));
+    assert_eq!(Mod101::from_digits(&[1, 0, 1, 1], 2), Mod101::new(11));
+    assert_eq!(Mod101::from_digits(&[], 10), Mod101::new(0));
+    assert_eq!(Mod101::from_digits(&[9; 12], 10), Mod101::from_u128(999_999_999_999));
+}
+
+#[test]
+fn test_try_from_digits() {
+    assert_eq!(Mod101::try_from_digits(&[1, 2, 3], 10), Some(Mod101::new(123)));
+    assert_eq!(Mod101::try_from_digits(&[1, 10, 3], 10), None);
+    assert_eq!(Mod101::try_from_digits(&[1, 2], 2), None);
+    // base larger than the modulus is allowed
+    assert_eq!(Mod101::try_from_digits(&[150, 1], 200), Some(Mod101::new(30001)));
+}
+
+#[test]
+fn test_bounded() {
+    assert_eq!(<Mod101 as Bounded>::min_value(), Mod101::new(0));
+    assert_eq!(<Mod101 as Bounded>::max_value(), Mod101::new(100));
+    assert_eq!(<Mod15 as Bounded>::max_value().remainder(), 14);
+    assert_eq!(<Mod101 as Bounded>::max_value() + Mod101::new(1), <Mod101 as Bounded>::min_value());
+}
+
+#[test]
+fn test_list_prime_factors() {
+    assert_eq!(crate::list_prime_factors(1_u32), []);
+    assert_eq!(crate::list_prime_factors(100_u32), [2, 5]);
+    assert_eq!(crate::list_prime_factors(101_u32), [101]);
+    assert_eq!(crate::list_prime_factors(2 * 3 * 1_000_000_007_u64), [2, 3, 1_000_000_007]);
+}
+
+#[test]
+fn test_power_tower() {
+    assert_eq!(Mod101::power_tower(&[]), Mod101::new(1));
+    assert_eq!(Mod101::power_tower(&[205]), Mod101::new(3));
+    assert_eq!(Mod101::power_tower(&[2, 3, 2]), Mod101::from_u128(512));
+    assert_eq!(Mod101::power_tower(&[3, 3, 3]), Mod101::from_u128(7_625_597_484_987));
+    assert_eq!(Mod101::power_tower(&[2, 2, 2, 2]), Mod101::from_u128(65536));
+    assert_eq!(Mod101::power_tower(&[7, 10, 20]), Mod101::new(7).pow_u128(100_000_000_000_000_000_000));
+}
+
+#[test]
+fn test_power_tower_zero() {
+    assert_eq!(Mod101::power_tower(&[0, 0]), Mod101::new(1));
+    assert_eq!(Mod101::power_tower(&[0, 5]), Mod101::new(0));
+    assert_eq!(Mod101::power_tower(&[7, 0]), Mod101::new(1));
+    assert_eq!(Mod101::power_tower(&[2, 0, 5]), Mod101::new(1));
+    assert_eq!(Mod101::power_tower(&[101, 2]), Mod101::new(0));
+}
+
+#[test]
+fn test_power_tower_composite() {
+    // 2 is not coprime to 100
+    assert_eq!(Mod100::power_tower(&[2, 100, 3]), Mod100::new(2).pow_u128(1_000_000));
+    assert_eq!(Mod100::power_tower(&[2, 3, 4]), Mod100::new(2).pow_u128(81));
+    assert_eq!(Mod100::power_tower(&[10, 10, 10]), Mod100::new(0));
+    // φ(100) = 40 and 3 is coprime to 100
+    assert_eq!(Mod100::power_tower(&[3, 4, 5, 6]), Mod100::new(3).pow_u128(crate::pow_mod_u128(4, 5_u128.pow(6), 40)));
+    for &a in &[2_u128, 4, 5, 6, 10, 12] {
+        for e in 0..30 {
+            assert_eq!(Mod100::power_tower(&[a, e]), Mod100::from_u128(a).pow_u128(e), "{}^{}", a, e);
+        }
+    }
+}
+
+#[test]
+fn test_try_from_char() {
+    for (i, c) in ('0'..='9').enumerate() {
+        assert_eq!(Mod101::try_from(c), Ok(Mod101::from(i as u16)));
+    }
+    assert_eq!(Mod101::try_from('a'), Err(ModularError::InvalidDigit('a')));
+    assert_eq!(Mod101::try_from('٣'), Err(ModularError::InvalidDigit('٣')));
+}
+
+#[test]
+fn test_try_from_char_hash() {
+    let hash = "31415".chars().try_fold(Mod101::new(0), |acc, c| Mod101::try_from(c).map(|d| acc * Mod101::new(10) + d));
+    assert_eq!(hash, Ok(Mod101::new(31415 % 101)));
+    assert_eq!(format!("{}", ModularError::InvalidDigit('x')), "'x' is not an ASCII decimal digit");
+}
+
+#[test]
+fn test_weighted_powers() {
+    let base = Mod101::new(10);
+    assert_eq!(Mod101::weighted_powers(base, &[]), Mod101::new(0));
+    // 3 + 2 * 10 + 1 * 100 = 123
+    let coeffs = [Mod101::new(3), Mod101::new(2), Mod101::new(1)];
+    assert_eq!(Mod101::weighted_powers(base, &coeffs), Mod101::new(123));
+
+    let base = Mod65521::new(31);
+    let coeffs: Vec<_> = (0..50_u32).map(|i| Mod65521::from(i * i + 7)).collect();
+    let explicit = coeffs.iter().enumerate()
+        .fold(Mod65521::new(0), |acc, (i, &c)| acc + c * base.pow(i));
+    assert_eq!(Mod65521::weighted_powers(base, &coeffs), explicit);
+}
+
+#[test]
+fn test_difference_prefix() {
+    let items = mod101_vec(&[5, 3, 100, 0, 42]);
+    let diff = Mod101::difference_prefix(&items);
+    assert_eq!(diff, mod101_vec(&[5, -2, 97, -100, 42]));
+    assert_eq!(Mod101::prefix_sum(&diff), items);
+    assert_eq!(Mod101::difference_prefix(&Mod101::prefix_sum(&items)), items);
+    assert_eq!(Mod101::prefix_sum(&[]), []);
+
+    // adding 10 to items[1..4] is two point updates on the difference array
+    let mut diff = diff;
+    diff[1] += Mod101::new(10);
+    diff[4] -= Mod101::new(10);
+    assert_eq!(Mod101::prefix_sum(&diff), mod101_vec(&[5, 13, 110, 10, 42]));
+}
+
+#[test]
+fn test_cycle_from() {
+    let start = Mod101::new(5);
+    let step = Mod101::new(7);
+    let values: Vec<_> = Mod101::cycle_from(start, step).take(102).collect();
+    assert_eq!(values[..3], [Mod101::new(5), Mod101::new(12), Mod101::new(19)]);
+    assert_eq!(values[101], start);
+    assert!(values[1..101].iter().all(|&x| x != start));
+
+    let values: Vec<_> = Mod15::cycle_from(Mod15::new(1), Mod15::new(5)).take(4).collect();
+    assert_eq!(values, [Mod15::new(1), Mod15::new(6), Mod15::new(11), Mod15::new(1)]);
+}
+
+#[test]
+fn test_geometric_sum_vec() {
+    assert_eq!(Mod101::new(2).geometric_sum_vec(0), []);
+    let sums = Mod101::new(2).geometric_sum_vec(10);
+    let expected: Vec<_> = (1..=10).map(|k| Mod101::from((1_u16 << k) - 1)).collect();
+    assert_eq!(sums, expected);
+}
+
+#[test]
+fn test_geometric_sum_vec_cumulative() {
+    let r = Mod101::new(57);
+    let sums = r.geometric_sum_vec(300);
+    let mut manual = Mod101::new(0);
+    for (k, &sum) in sums.iter().enumerate() {
+        manual += r.pow(k);
+        assert_eq!(sum, manual);
+    }
+    // the ratio 1 counts the terms
+    assert_eq!(Mod101::new(1).geometric_sum_vec(101)[100], Mod101::new(0));
+}
+
+#[test]
+fn test_bytes_round_trip() {
+    for i in (0..65521).step_by(97) {
+        let x = Mod65521::from(i as u32);
+        assert_eq!(Mod65521::from_le_bytes(x.to_le_bytes()), x);
+        assert_eq!(Mod65521::from_be_bytes(x.to_be_bytes()), x);
+    }
+}
+
+#[test]
+fn test_bytes_endianness() {
+    let x = Mod65521::new(0x0102);
+    assert_eq!(x.to_le_bytes(), [2, 1, 0, 0]);
+    assert_eq!(x.to_be_bytes(), [0, 0, 1, 2]);
+    assert_eq!(Mod65521::from_be_bytes([2, 1, 0, 0]), Mod65521::from(0x0201_0000 % 65521));
+    assert_eq!(Mod101::new(5).to_le_bytes(), [5, 0]);
+}
+
+#[test]
+fn test_bytes_reduce() {
+    assert_eq!(Mod101::from_le_bytes([0xff, 0xff]), Mod101::from(u16::MAX));
+    assert_eq!(Mod101::from_be_bytes([0, 101]), Mod101::new(0));
+}
+
+#[test]
+fn test_pow_limbs_single() {
+    let x = Mod65521::new(12345);
+    for &n in &[0_u64, 1, 2, 65519, 65520, u64::MAX] {
+        assert_eq!(x.pow_limbs(&[n]), x.pow_u128(n as u128));
+    }
+    assert_eq!(x.pow_limbs(&[]), Mod65521::new(1));
+}
+
+#[test]
+fn test_pow_limbs_multiple() {
+    let x = Mod65521::new(12345);
+    let n = 0x0123_4567_89ab_cdef_fedc_ba98_7654_3210_u128;
+    assert_eq!(x.pow_limbs(&[n as u64, (n >> 64) as u64]), x.pow_u128(n));
+    // trailing zero limbs do not change the result
+    assert_eq!(x.pow_limbs(&[n as u64, (n >> 64) as u64, 0, 0]), x.pow_u128(n));
+    // x^(2^192) = ((x^(2^64))^(2^64))^(2^64)
+    let x192 = x.pow_u128(1 << 64).pow_u128(1 << 64).pow_u128(1 << 64);
+    assert_eq!(x.pow_limbs(&[0, 0, 0, 1]), x192);
+}
+
+#[test]
+fn test_sqrt_3mod4_residues() {
+    let mut residues = 0;
+    for i in 0..103_u16 {
+        let x = Mod103::from(i);
+        let square = x * x;
+        let root = square.sqrt_3mod4().unwrap();
+        assert_eq!(root * root, square);
+        assert_eq!(root, x.min(Mod103::new(0) - x));
+        assert_eq!(Some(root), square.sqrt());
+        if x.sqrt_3mod4().is_some() {
+            residues += 1;
+        }
+    }
+    // 0 and the (103 - 1) / 2 nonzero quadratic residues
+    assert_eq!(residues, 52);
+}
+
+#[test]
+fn test_sqrt_3mod4_non_residue() {
+    // -1 is a non-residue for primes congruent to 3 modulo 4
+    assert_eq!(Mod103::new(-1).sqrt_3mod4(), None);
+    // 5 is a non-residue modulo 103
+    assert_eq!(Mod103::new(5).sqrt_3mod4(), None);
+}
+
+#[test]
+fn test_sqrt_3mod4_unsupported_modulus() {
+    assert_eq!(Mod101::new(4).sqrt_3mod4(), None);
+    assert_eq!(Mod15::new(4).sqrt_3mod4(), None);
+}
+
+#[test]
+fn test_assert_field_axioms() {
+    Mod101::assert_field_axioms();
+    Mod103::assert_field_axioms();
+    Mod65521::assert_field_axioms();
+}
+
+#[test]
+fn test_assert_field_axioms_ring() {
+    Mod100::assert_field_axioms();
+    Mod15::assert_field_axioms();
+}
+
+#[test]
+fn test_inv_2() {
+    assert_eq!(Mod101::INV_2.unwrap() * Mod101::new(2), Mod101::new(1));
+    assert_eq!(Mod101::INV_2, Mod101::new(2).inv());
+    assert_eq!(Mod15::INV_2.unwrap() * Mod15::new(2), Mod15::new(1));
+    assert_eq!(Mod100::INV_2, None);
+}
+
+#[test]
+fn test_halve() {
+    assert_eq!(Mod101::new(10).halve(), Some(Mod101::new(5)));
+    assert_eq!(Mod101::new(1).halve().map(|x| x + x), Some(Mod101::new(1)));
+    assert_eq!(Mod100::new(10).halve(), None);
+}
+
+#[test]
+fn test_modpow_macro() {
+    let x = Mod101::new(37);
+    assert_eq!(crate::modpow!(x, 0), x.pow(0));
+    assert_eq!(crate::modpow!(x, 1), x.pow(1));
+    assert_eq!(crate::modpow!(x, 2), x.pow(2));
+    assert_eq!(crate::modpow!(x, 3), x.pow(3));
+    assert_eq!(crate::modpow!(x, 4), x.pow(4));
+    assert_eq!(crate::modpow!(x, 5), x.pow(5));
+    assert_eq!(crate::modpow!(x, 6), x.pow(6));
+    assert_eq!(crate::modpow!(x, 7), x.pow(7));
+    assert_eq!(crate::modpow!(x, 8), x.pow(8));
+    assert_eq!(crate::modpow!(x, 9), x.pow(9));
+    assert_eq!(crate::modpow!(x, 10), x.pow(10));
+    assert_eq!(crate::modpow!(x, 11), x.pow(11));
+    assert_eq!(crate::modpow!(x, 12), x.pow(12));
+    assert_eq!(crate::modpow!(x, 13), x.pow(13));
+    assert_eq!(crate::modpow!(x, 14), x.pow(14));
+    assert_eq!(crate::modpow!(x, 15), x.pow(15));
+    assert_eq!(crate::modpow!(x, 16), x.pow(16));
+}
+
+#[test]
+fn test_modpow_macro_fallback() {
+    let x = Mod101::new(37);
+    assert_eq!(crate::modpow!(x, 17), x.pow(17));
+    assert_eq!(crate::modpow!(x, 1000), x.pow(1000));
+    assert_eq!(crate::modpow!(x + Mod101::new(1), 13), Mod101::new(38).pow(13));
+}
+
+#[test]
+fn test_try_from_i128() {
+    assert_eq!(Mod101::try_from_i128(0), Ok(Mod101::new(0)));
+    assert_eq!(Mod101::try_from_i128(205), Ok(Mod101::new(3)));
+    assert_eq!(Mod101::try_from_i128(-1), Ok(Mod101::new(100)));
+    assert_eq!(Mod101::try_from_i128(i128::MIN), Ok(Mod101::from_u128((i128::MIN).rem_euclid(101) as u128)));
+    assert_eq!(Mod101::try_from_i128(i128::MAX), Ok(Mod101::from_u128(i128::MAX as u128)));
+    for x in -1000..1000 {
+        assert!(Mod15::try_from_i128(x).is_ok());
+    }
+}
+
+#[test]
+fn test_try_from_i128_question_mark() {
+    fn parse_and_double(c: char, offset: i128) -> Result<Mod101, ModularError> {
+        let digit = Mod101::try_from(c)?;
+        Ok(digit + Mod101::try_from_i128(offset)? * Mod101::new(2))
+    }
+    assert_eq!(parse_and_double('7', -3), Ok(Mod101::new(1)));
+    assert_eq!(parse_and_double('x', 0), Err(ModularError::InvalidDigit('x')));
+}
+
+#[test]
+fn test_primitive_root() {
+    assert_eq!(Mod101::primitive_root(), Some(Mod101::new(2)));
+    assert_eq!(Mod103::primitive_root(), Some(Mod103::new(5)));
+    assert_eq!(Mod65521::primitive_root(), Some(Mod65521::new(17)));
+    assert_eq!(Mod100::primitive_root(), None);
+    assert_eq!(Mod15::primitive_root(), None);
+}
+
+#[test]
+fn test_cached_primitive_root() {
+    let g = Mod101::cached_primitive_root().unwrap();
+    assert_eq!(Mod101::cached_primitive_root(), Some(g));
+    assert_eq!(Mod101::cached_primitive_root(), Mod101::primitive_root());
+    // g has full order 100
+    let mut x = g;
+    for _ in 1..100 {
+        assert_ne!(x, Mod101::new(1));
+        x *= g;
+    }
+    assert_eq!(x, Mod101::new(1));
+    assert_eq!(Mod100::cached_primitive_root(), None);
+}
+
+#[test]
+fn test_is_primitive_root() {
+    let count = (0..101_u16).filter(|&i| Mod101::from(i).is_primitive_root()).count();
+    // φ(100) = 40
+    assert_eq!(count, 40);
+    assert!(!Mod101::new(1).is_primitive_root());
+    assert!(!Mod100::new(3).is_primitive_root());
+}
+
+#[test]
+fn test_from_non_zero() {
+    assert_eq!(Mod101::from(NonZeroU16::new(5).unwrap()), Mod101::new(5));
+    assert_eq!(Mod101::from(NonZeroU16::new(u16::MAX).unwrap()), Mod101::from(u16::MAX));
+    // reduction may produce zero
+    assert_eq!(Mod15::from(NonZeroU8::new(30).unwrap()), Mod15::new(0));
+}
+
+#[test]
+fn test_div() {
+    assert_eq!(Mod101::new(10) / Mod101::new(5), Mod101::new(2));
+    assert_eq!(Mod101::new(1) / Mod101::new(2), Mod101::new(51));
+    assert_eq!(Mod100::new(21) / Mod100::new(3), Mod100::new(7));
+}
+
+#[test]
+#[should_panic(expected = "Modular inverse of 10 does not exist modulo 100")]
+fn test_div_non_unit_message() {
+    let _ = Mod100::new(3) / Mod100::new(10);
+}
+
+#[test]
+#[should_panic(expected = "Modular inverse of 0 does not exist modulo 101")]
+fn test_div_zero_message() {
+    let _ = Mod101::new(3) / Mod101::new(0);
+}
+
+#[test]
+fn test_mul_scalar_wide() {
+    assert_eq!(Mod101::new(3).mul_scalar_wide(5), Mod101::new(15));
+    assert_eq!(Mod101::new(3).mul_scalar_wide(u128::MAX), Mod101::new(3) * Mod101::from_u128(u128::MAX));
+    assert_eq!(Mod65521::new(65520).mul_scalar_wide(1 << 100), Mod65521::new(-1) * Mod65521::from_u128(1 << 100));
+    assert_eq!(Mod101::new(50).mul_scalar_wide(101 * 1_000_000_000_000), Mod101::new(0));
+}
+
+#[test]
+fn test_pow_mod_order_identity() {
+    for i in 1..101_u16 {
+        let unit = Mod101::from(i);
+        for &k in &[1_u128, 2, 12345, u128::MAX / 100] {
+            assert_eq!(unit.pow_mod_order(k * 100), Mod101::new(1));
+            assert_eq!(unit.pow_u128(k * 100), Mod101::new(1));
+        }
+    }
+}
+
+#[test]
+fn test_pow_mod_order_zero_base() {
+    let zero = Mod101::new(0);
+    assert_eq!(zero.pow_mod_order(0), Mod101::new(1));
+    for &exp in &[1_u128, 2, 100, 200, 100 * 12345, u128::MAX] {
+        assert_eq!(zero.pow_mod_order(exp), zero);
+    }
+    assert_eq!(Mod100::new(0).pow_mod_order(0), Mod100::new(1));
+    assert_eq!(Mod100::new(0).pow_mod_order(40), Mod100::new(0));
+    assert_eq!(Mod100::new(0).pow_mod_order_with(80, 40), Mod100::new(0));
+}
+
+#[test]
+fn test_pow_one_base() {
+    assert_eq!(Mod101::new(1).pow(usize::MAX), Mod101::new(1));
+    assert_eq!(Mod100::new(1).pow_u128(u128::MAX), Mod100::new(1));
+    assert_eq!(Mod2p89::new(1).pow_u128(u128::MAX), Mod2p89::new(1));
+    assert_eq!(Mod101::new(1).pow(0), Mod101::new(1));
+}
+
+#[test]
+fn test_pow_elem() {
+    let x = Mod101::new(3);
+    for e in 0..101_u16 {
+        assert_eq!(x.pow_elem(Mod101::from(e)), x.pow(e as usize));
+    }
+    // 3^100 = 1 by Fermat's little theorem
+    assert_eq!(x.pow_elem(Mod101::new(100)), Mod101::new(1));
+    assert_eq!(Mod101::new(0).pow_elem(Mod101::new(0)), Mod101::new(1));
+    assert_eq!(Mod101::new(0).pow_elem(Mod101::new(5)), Mod101::new(0));
+    // non-units of a composite modulus are not reduced
+    assert_eq!(Mod100::new(2).pow_elem(Mod100::new(45)), Mod100::new(2).pow(45));
+    assert_eq!(Mod100::new(3).pow_elem(Mod100::new(99)), Mod100::new(3).pow(99));
+    // the exponent wraps around MOD, not the group order
+    let (a, b) = (Mod101::new(60), Mod101::new(50));
+    assert_ne!(x.pow_elem(a + b), x.pow_elem(a) * x.pow_elem(b));
+}
+
+#[test]
+fn test_pow_mod_order() {
+    let x = Mod101::new(42);
+    assert_eq!(x.pow_mod_order(1_000_003), crate::pow_binary(x, 1_000_003));
+    // composite moduli reduce by φ(100) = 40 for units only
+    assert_eq!(Mod100::new(3).pow_mod_order(41), Mod100::new(3));
+    assert_eq!(Mod100::new(2).pow_mod_order(41), crate::pow_binary(Mod100::new(2), 41));
+}
+
+#[test]
+fn test_inv_table() {
+    let table = Mod101::inv_table().unwrap();
+    assert_eq!(table.len(), 101);
+    assert_eq!(table[0], Mod101::new(0));
+    for i in 1..101_u16 {
+        assert_eq!(Some(table[i as usize]), Mod101::from(i).inv());
+    }
+}
+
+#[test]
+fn test_inv_table_checked_prime() {
+    let table = Mod101::inv_table_checked();
+    assert_eq!(table[0], None);
+    for i in 1..101_u16 {
+        assert_eq!(table[i as usize], Mod101::from(i).inv());
+    }
+}
+
+#[test]
+fn test_inv_table_checked_composite() {
+    let table = Mod100::inv_table_checked();
+    assert_eq!(table.len(), 100);
+    for i in 0..100_u16 {
+        let x = Mod100::from(i);
+        match table[i as usize] {
+            Some(inv) => assert_eq!(x * inv, Mod100::new(1)),
+            None => assert!(i % 2 == 0 || i % 5 == 0, "{} is a unit", i),
+        }
+        assert_eq!(table[i as usize].is_some(), crate::gcd(i, 100) == 1);
+    }
+    let table = Mod15::inv_table_checked();
+    assert_eq!(table.iter().filter(|inv| inv.is_some()).count(), 8);
+}
+
+#[test]
+fn test_mul_exhaustive_wide_u8() {
+    for a in 0..63_u8 {
+        for b in 0..63_u8 {
+            let expected = (a as u16 * b as u16 % 63) as u8;
+            assert_eq!((Mod63::from(a) * Mod63::from(b)).remainder(), expected, "{} * {}", a, b);
+        }
+    }
+    Mod63::assert_field_axioms();
+}
+
+#[test]
+fn test_u128_above_2_64() {
+    // reference values computed with arbitrary-precision integers
+    let a = Mod2p89::from(123_456_789_012_345_678_901_234_567_u128);
+    let b = Mod2p89::from(98_765_432_109_876_543_210_987_654_u128);
+    assert!(crate::const_is_prime(Mod2p89::MOD));
+    assert_eq!(a * b, Mod2p89::from(37_582_974_825_495_905_956_867_967_u128));
+    assert_eq!(a * b * a, Mod2p89::from(358_156_215_344_172_434_937_741_054_u128));
+    assert_eq!(a.pow_u128(1_000_000_000_000_000_000_000_000_000_007), Mod2p89::from(194_749_796_702_671_513_047_137_563_u128));
+    assert_eq!(a.inv(), Some(Mod2p89::from(430_073_109_346_628_171_467_792_010_u128)));
+    assert_eq!(a * a.inv().unwrap(), Mod2p89::new(1));
+    assert_eq!(a.pow_u128(Mod2p89::MOD - 1), Mod2p89::new(1));
+    assert_eq!(a - b + b, a);
+}
+
+#[test]
+fn test_modulus_is_safe_prime() {
+    // 107 = 2 * 53 + 1
+    assert!(Mod107::modulus_is_safe_prime());
+    // 2^89 - 1 = 2 * (2^88 - 1) + 1, and 2^88 - 1 is divisible by 3
+    assert!(!Mod2p89::modulus_is_safe_prime());
+    // 101 = 2 * 50 + 1
+    assert!(!Mod101::modulus_is_safe_prime());
+    assert!(!Mod100::modulus_is_safe_prime());
+    assert!(!Mod15::modulus_is_safe_prime());
+}
+
+#[test]
+fn test_from_raw_iter() {
+    let raw = [0_u16, 1, 100, 101, 202, 5000, u16::MAX];
+    let manual: Vec<_> = raw.iter().map(|&x| Mod101::from(x)).collect();
+    assert_eq!(Mod101::from_raw_iter(raw.iter().copied()), manual);
+    assert_eq!(Mod101::from_raw_iter(Vec::new()), []);
+    assert_eq!(Mod101::from_raw_iter(100..103), [Mod101::new(100), Mod101::new(0), Mod101::new(1)]);
+}
+
+crate::def_modular!(WidthU8 : u8 | i8, 61 ; width_u8_lbl);
+crate::def_modular!(WidthU16 : u16 | i16, 16381 ; width_u16_lbl);
+crate::def_modular!(WidthU32 : u32 | i32, 1_000_000_007 ; width_u32_lbl);
+crate::def_modular!(WidthU64 : u64 | i64, 2_305_843_009_213_693_951 ; width_u64_lbl);
+crate::def_modular!(WidthU128 : u128 | i128, 1_000_000_007 ; width_u128_lbl);
+
+macro_rules! pow_width_test {
+    ($test:ident, $name:ident, $int:ty) => {
+        #[test]
+        fn $test() {
+            let modulus = $name::MOD as u128;
+            for &base in &[0_u128, 1, 2, 3, modulus / 2, modulus - 1] {
+                let x = $name::from(base as $int);
+                let mut expected = 1_u128;
+                for n in 0..200 {
+                    assert_eq!(x.pow(n).remainder() as u128, expected, "{}^{} mod {}", base, n, modulus);
+                    assert_eq!(x.pow_u128(n as u128).remainder() as u128, expected, "{}^{} mod {}", base, n, modulus);
+                    expected = crate::mul_mod_u128(expected, base, modulus);
+                }
+            }
+            // Fermat's little theorem for nonzero bases
+            assert_eq!($name::from(2 as $int).pow_u128(modulus - 1), $name::from(1 as $int));
+        }
+    };
+}
+
+pow_width_test!(test_pow_width_u8, WidthU8, u8);
+pow_width_test!(test_pow_width_u16, WidthU16, u16);
+pow_width_test!(test_pow_width_u32, WidthU32, u32);
+pow_width_test!(test_pow_width_u64, WidthU64, u64);
+pow_width_test!(test_pow_width_u128, WidthU128, u128);
+
+// the largest moduli accepted by the overflow assertion without the `checked` feature,
+// i.e. $sint::MAX / 2
+crate::def_modular!(MaxU8 : u8 | i8, 63 ; max_u8_lbl);
+crate::def_modular!(MaxU16 : u16 | i16, 16383 ; max_u16_lbl);
+crate::def_modular!(MaxU32 : u32 | i32, 1_073_741_823 ; max_u32_lbl);
+crate::def_modular!(MaxU64 : u64 | i64, 4_611_686_018_427_387_903 ; max_u64_lbl);
+
+macro_rules! arithmetic_width_test {
+    ($test:ident, $name:ident, $int:ty) => {
+        #[test]
+        fn $test() {
+            let modulus = $name::MOD as u128;
+            let edges = [0, 1, 2, 3, modulus / 2, modulus / 2 + 1, modulus - 2, modulus - 1];
+            for &a in &edges {
+                let x = $name::from(a as $int);
+                for &b in &edges {
+                    let y = $name::from(b as $int);
+                    assert_eq!((x - y).remainder() as u128, (a + modulus - b) % modulus, "{} - {} mod {}", a, b, modulus);
+                    assert_eq!((x + y).remainder() as u128, (a + b) % modulus, "{} + {} mod {}", a, b, modulus);
+                    assert_eq!((x * y).remainder() as u128, crate::mul_mod_u128(a, b, modulus), "{} * {} mod {}", a, b, modulus);
+                }
+                assert_eq!((-x).remainder() as u128, (modulus - a) % modulus);
+                let mut expected = 1 % modulus;
+                for n in 0..64 {
+                    assert_eq!(x.pow(n).remainder() as u128, expected, "{}^{} mod {}", a, n, modulus);
+                    expected = crate::mul_mod_u128(expected, a, modulus);
+                }
+                if a != 0 && $name::IS_FIELD {
+                    assert_eq!(x.pow_u128(modulus - 1), $name::from(1 as $int), "{}^(MOD - 1) mod {}", a, modulus);
+                }
+            }
+        }
+    };
+}
+
+arithmetic_width_test!(test_arithmetic_max_u8, MaxU8, u8);
+arithmetic_width_test!(test_arithmetic_max_u16, MaxU16, u16);
+arithmetic_width_test!(test_arithmetic_max_u32, MaxU32, u32);
+arithmetic_width_test!(test_arithmetic_max_u64, MaxU64, u64);
+arithmetic_width_test!(test_arithmetic_max_u128, ModMaxU128, u128);
+
+// with the `checked` feature the moduli may reach $sint::MAX, so lhs.0 + $mod would overflow
+#[cfg(feature = "checked")]
+crate::def_modular!(CheckedMaxU8 : u8 | i8, 127 ; checked_max_u8_lbl);
+#[cfg(feature = "checked")]
+crate::def_modular!(CheckedMaxU64 : u64 | i64, 9_223_372_036_854_775_807 ; checked_max_u64_lbl);
+#[cfg(feature = "checked")]
+arithmetic_width_test!(test_arithmetic_checked_max_u8, CheckedMaxU8, u8);
+#[cfg(feature = "checked")]
+arithmetic_width_test!(test_arithmetic_checked_max_u64, CheckedMaxU64, u64);
+
+// moduli on either side of the largest M with M * M fitting in the base type, where
+// multiplication switches between the native product and the u128 fallback
+crate::def_modular!(SquareFitsU8 : u8 | i8, 13 ; square_fits_u8_lbl);
+crate::def_modular!(SquareOverflowsU8 : u8 | i8, 17 ; square_overflows_u8_lbl);
+crate::def_modular!(SquareFitsU16 : u16 | i16, 251 ; square_fits_u16_lbl);
+crate::def_modular!(SquareOverflowsU16 : u16 | i16, 257 ; square_overflows_u16_lbl);
+crate::def_modular!(SquareFitsU32 : u32 | i32, 65521 ; square_fits_u32_lbl);
+crate::def_modular!(SquareOverflowsU32 : u32 | i32, 65537 ; square_overflows_u32_lbl);
+crate::def_modular!(SquareFitsU64 : u64 | i64, 4_294_967_291 ; square_fits_u64_lbl);
+crate::def_modular!(SquareOverflowsU64 : u64 | i64, 4_294_967_311 ; square_overflows_u64_lbl);
+crate::def_modular!(SquareOverflowsU128 : u128 | i128, 18_446_744_073_709_551_629 ; square_overflows_u128_lbl);
+
+arithmetic_width_test!(test_arithmetic_square_fits_u8, SquareFitsU8, u8);
+arithmetic_width_test!(test_arithmetic_square_overflows_u8, SquareOverflowsU8, u8);
+arithmetic_width_test!(test_arithmetic_square_fits_u16, SquareFitsU16, u16);
+arithmetic_width_test!(test_arithmetic_square_overflows_u16, SquareOverflowsU16, u16);
+arithmetic_width_test!(test_arithmetic_square_fits_u32, SquareFitsU32, u32);
+arithmetic_width_test!(test_arithmetic_square_overflows_u32, SquareOverflowsU32, u32);
+arithmetic_width_test!(test_arithmetic_square_fits_u64, SquareFitsU64, u64);
+arithmetic_width_test!(test_arithmetic_square_overflows_u64, SquareOverflowsU64, u64);
+arithmetic_width_test!(test_arithmetic_square_fits_u128, Mod2p64m59, u128);
+arithmetic_width_test!(test_arithmetic_square_overflows_u128, SquareOverflowsU128, u128);
+
+fn brute_force_kth_powers<M: Modular<u16>>(k: usize) -> Vec<M> {
+    (0..M::MOD).map(|x| M::from(x).pow(k)).collect()
+}
+
+#[test]
+fn test_has_kth_root_prime() {
+    for k in 0..12 {
+        let powers = brute_force_kth_powers::<Mod101>(k);
+        for i in 0..101_u16 {
+            let x = Mod101::from(i);
+            assert_eq!(x.has_kth_root(k), Some(powers.contains(&x)), "{:?} has_kth_root({})", x, k);
+        }
+    }
+    // 100 = 2^2 * 5^2, so every element is a 3rd power
+    assert!((0..101_u16).all(|i| Mod101::from(i).has_kth_root(3) == Some(true)));
+}
+
+#[test]
+fn test_has_kth_root_composite() {
+    for k in 0..6 {
+        assert_eq!(Mod100::new(4).has_kth_root(k), None);
+    }
+    assert_eq!(Mod100::new(1).has_kth_root(0), None);
+}
+
+#[test]
+fn test_from_rational() {
+    assert_eq!(Mod101::from_rational(1, 2), Some(Mod101::new(51)));
+    assert_eq!(Mod101::from_rational(-3, 7).map(|x| x * Mod101::new(7)), Some(Mod101::new(-3)));
+    assert_eq!(Mod101::from_rational(1, 101), None);
+    assert_eq!(Mod100::from_rational(1, 4), None);
+}
+
+#[test]
+fn test_rational_reconstruct() {
+    assert_eq!(Mod101::from_rational(3, 7).unwrap().rational_reconstruct(), Some((3, 7)));
+    assert_eq!(Mod101::from_rational(-2, 5).unwrap().rational_reconstruct(), Some((-2, 5)));
+    assert_eq!(Mod101::new(5).rational_reconstruct(), Some((5, 1)));
+    assert_eq!(Mod101::new(-5).rational_reconstruct(), Some((-5, 1)));
+    let big = WidthU32::from_rational(-12345, 678).unwrap();
+    assert_eq!(big.rational_reconstruct(), Some((-4115, 226)));
+}
+
+#[test]
+fn test_to_fraction_string() {
+    assert_eq!(Mod101::from_rational(3, 7).unwrap().to_fraction_string(), "3/7");
+    assert_eq!(Mod101::from_rational(-1, 2).unwrap().to_fraction_string(), "-1/2");
+    assert_eq!(Mod101::from_rational(14, 7).unwrap().to_fraction_string(), "2");
+    assert_eq!(WidthU32::from_rational(1, 999).unwrap().to_fraction_string(), "1/999");
+}
+
+fn mod101_vec(values: &[i16]) -> Vec<Mod101> { values.iter().map(|&x| Mod101::new(x)).collect() }
+
+#[test]
+fn test_linear_basis_independent() {
+    let mut basis = LinearBasis::new(3);
+    assert_eq!(basis.rank(), 0);
+    assert!(basis.insert(&mod101_vec(&[1, 2, 3])));
+    assert!(basis.insert(&mod101_vec(&[0, 5, 7])));
+    assert!(basis.insert(&mod101_vec(&[4, 0, 1])));
+    assert_eq!(basis.rank(), 3);
+    // the basis spans the whole space now
+    assert!(!basis.insert(&mod101_vec(&[9, 9, 9])));
+    assert_eq!(basis.rank(), 3);
+}
+
+#[test]
+fn test_linear_basis_dependent() {
+    let mut basis = LinearBasis::new(4);
+    let a = mod101_vec(&[1, 2, 3, 4]);
+    let b = mod101_vec(&[0, 1, 0, 100]);
+    assert!(basis.insert(&a));
+    assert!(basis.insert(&b));
+    // 3a - 7b
+    let combination: Vec<_> = a.iter().zip(&b).map(|(&x, &y)| Mod101::new(3) * x - Mod101::new(7) * y).collect();
+    assert!(basis.contains(&combination));
+    assert!(!basis.insert(&combination));
+    // 51a, with coordinates beyond the modulus
+    assert!(!basis.insert(&mod101_vec(&[51, 102, 153, 204])));
+    assert!(!basis.insert(&mod101_vec(&[0, 0, 0, 0])));
+    assert_eq!(basis.rank(), 2);
+    assert!(!basis.contains(&mod101_vec(&[0, 0, 1, 0])));
+}
+
+#[test]
+fn test_linear_basis_reduced_form() {
+    let mut basis = LinearBasis::new(3);
+    basis.insert(&mod101_vec(&[2, 4, 6]));
+    basis.insert(&mod101_vec(&[0, 3, 1]));
+    let vectors: Vec<_> = basis.vectors().collect();
+    assert_eq!(vectors[0][0], Mod101::new(1));
+    assert_eq!(vectors[0][1], Mod101::new(0));
+    assert_eq!(vectors[1][1], Mod101::new(1));
+}
+
+#[test]
+fn test_legendre() {
+    let squares: Vec<_> = (1..101_u16).map(|i| Mod101::from(i) * Mod101::from(i)).collect();
+    assert_eq!(Mod101::new(0).legendre(), Some(0));
+    for i in 1..101_u16 {
+        let x = Mod101::from(i);
+        let expected = if squares.contains(&x) { 1 } else { -1 };
+        assert_eq!(x.legendre(), Some(expected), "{:?}", x);
+    }
+}
+
+#[test]
+fn test_sqrt() {
+    for i in 0..101_u16 {
+        let square = Mod101::from(i) * Mod101::from(i);
+        assert_eq!(square.sqrt().map(|r| r * r), Some(square));
+    }
+    for i in 0..103_u16 {
+        let square = Mod103::from(i) * Mod103::from(i);
+        assert_eq!(square.sqrt().map(|r| r * r), Some(square));
+    }
+    // 65521 - 1 = 16 * 4095, exercising several Tonelli-Shanks iterations
+    for i in (1..65521_u32).step_by(101) {
+        let square = Mod65521::from(i) * Mod65521::from(i);
+        assert_eq!(square.sqrt().map(|r| r * r), Some(square));
+    }
+    assert_eq!(Mod101::new(2).sqrt(), None);
+}
+
+#[test]
+fn test_sqrt_canonical() {
+    for i in 0..101_u16 {
+        let root = Mod101::from(i);
+        let square = root * root;
+        let expected = root.min(Mod101::new(0) - root);
+        assert_eq!(square.sqrt(), Some(expected));
+        assert_eq!(square.sqrt(), square.sqrt());
+        assert!(expected.remainder() <= 50);
+    }
+    // the roots of 4 are 2 and 65519
+    assert_eq!(Mod65521::new(4).sqrt(), Some(Mod65521::new(2)));
+}
+
+#[test]
+fn test_field_only_methods_on_composite() {
+    assert_eq!(Mod100::new(4).legendre(), None);
+    assert_eq!(Mod100::new(4).sqrt(), None);
+    assert_eq!(Mod100::new(4).sqrt_3mod4(), None);
+    assert_eq!(Mod100::primitive_root(), None);
+    assert!(!Mod100::new(3).is_primitive_root());
+    assert_eq!(Mod100::inv_table(), None);
+    assert_eq!(Mod15::new(4).sqrt(), None);
+}
+
+#[test]
+fn test_pow2_arithmetic() {
+    for a in (0..1024_u32).step_by(7) {
+        for b in (0..1024_u32).step_by(11) {
+            let (x, y) = (Mod1024::from(a as u16), Mod1024::from(b as u16));
+            assert_eq!((x + y).remainder() as u32, (a + b) % 1024);
+            assert_eq!((x - y).remainder() as u32, (a + 1024 - b) % 1024);
+            assert_eq!((x * y).remainder() as u32, a * b % 1024);
+        }
+    }
+    assert_eq!(Mod256::new(255) * Mod256::new(255), Mod256::new(1));
+    assert_eq!(Mod256::new(200) + Mod256::new(100), Mod256::new(44));
+}
+
+#[test]
+fn test_pow2_inv() {
+    for a in 0..1024_u16 {
+        let x = Mod1024::from(a);
+        if a % 2 == 1 {
+            assert_eq!(x * x.inv().unwrap(), Mod1024::new(1));
+        } else {
+            assert_eq!(x.inv(), None);
+        }
+    }
+    for a in (1..256_u16).step_by(2) {
+        let x = Mod256::from(a);
+        assert_eq!(x.inv(), x.brute_force_inv());
+    }
+}
+
+#[test]
+fn test_newton_inv() {
+    for a in 0..256_u16 {
+        let x = Mod256::from(a);
+        match x.newton_inv() {
+            Some(inv) => assert_eq!(x * inv, Mod256::new(1)),
+            None => assert_eq!(a % 2, 0),
+        }
+    }
+    assert_eq!(Mod1024::new(1023).newton_inv(), Some(Mod1024::new(1023)));
+    assert_eq!(Mod1024::new(2).newton_inv(), None);
+    assert_eq!(Mod101::new(3).newton_inv(), None);
+}
+
+#[test]
+fn test_pow_checked_unit() {
+    let x = Mod101::new(3);
+    assert_eq!(x.pow_checked_unit(5), Some(x.pow(5)));
+    assert_eq!(x.pow_checked_unit(0), Some(Mod101::new(1)));
+    assert_eq!(x.pow_checked_unit(-5).map(|y| y * x.pow(5)), Some(Mod101::new(1)));
+    assert_eq!(Mod101::new(0).pow_checked_unit(-1), None);
+    assert_eq!(Mod101::new(0).pow_checked_unit(2), Some(Mod101::new(0)));
+    assert_eq!(Mod100::new(10).pow_checked_unit(-3), None);
+    assert_eq!(Mod100::new(10).pow_checked_unit(3), Some(Mod100::new(0)));
+    assert_eq!(Mod100::new(3).pow_checked_unit(-2).map(|y| y * Mod100::new(9)), Some(Mod100::new(1)));
+}
+
+#[test]
+fn test_dyn_modular_ops() {
+    let values: [Box<dyn DynModularOps>; 3] = [
+        Box::new(Mod101::new(3)),
+        Box::new(Mod15::new(3)),
+        Box::new(WidthU64::new(3)),
+    ];
+    let moduli: Vec<_> = values.iter().map(|v| v.modulus()).collect();
+    assert_eq!(moduli, [101, 15, 2_305_843_009_213_693_951]);
+    let fields: Vec<_> = values.iter().map(|v| v.is_field()).collect();
+    assert_eq!(fields, [true, false, true]);
+    for v in &values {
+        assert_eq!(v.residue(), 3);
+        assert_eq!(v.add_residue(v.modulus() - 1), 2);
+        assert_eq!(v.mul_residue(v.modulus() + 2), 6);
+        assert_eq!(v.pow_residue(2), 9);
+    }
+    assert_eq!(values[0].inv_residue(), Some(34));
+    assert_eq!(values[1].inv_residue(), None);
+}
+
+#[test]
+fn test_from_str() {
+    assert_eq!(" 102\n".parse(), Ok(Mod101::new(1)));
+    assert_eq!("\t-1 ".parse(), Ok(Mod101::new(100)));
+    assert_eq!("+0".parse(), Ok(Mod101::new(0)));
+    // longer than any primitive integer
+    assert_eq!("1".repeat(50).parse::<Mod101>(), Ok(Mod101::from_digits(&[1; 50], 10)));
+    assert_eq!("1 2".parse::<Mod101>(), Err(ModularError::InvalidDigit(' ')));
+    assert_eq!("12a".parse::<Mod101>(), Err(ModularError::InvalidDigit('a')));
+    assert_eq!("  ".parse::<Mod101>(), Err(ModularError::Empty));
+    assert_eq!("-".parse::<Mod101>(), Err(ModularError::Empty));
+    assert_eq!(format!("{}", ModularError::Empty), "cannot parse a modular value without digits");
+}
+
+#[test]
+fn test_fast_sum() {
+    assert_eq!(Mod101::fast_sum(&[]), Mod101::new(0));
+    let items: Vec<_> = (0..100_000_u32).map(|i| WidthU32::from(i.wrapping_mul(2_654_435_761))).collect();
+    assert_eq!(WidthU32::fast_sum(&items), items.iter().sum());
+
+    let items: Vec<_> = (0..10_000_i128).map(|i| Mod2p89::new(-i)).collect();
+    assert_eq!(Mod2p89::fast_sum(&items), items.iter().sum());
+
+    // only 4 representatives of this modulus fit in a u128 accumulator
+    let items: Vec<_> = (0..1001_i128).map(|i| ModMaxU128::new(-i)).collect();
+    assert_eq!(ModMaxU128::fast_sum(&items), items.iter().sum());
+}
+
+#[test]
+fn test_pow_windowed() {
+    let x = WidthU64::new(123_456_789);
+    for &n in &[0_u128, 1, 2, 255, 256, 1_000_000_007, u128::MAX] {
+        let expected = crate::pow_binary(x, n);
+        for window_bits in 1..=8 {
+            assert_eq!(x.pow_windowed(n, window_bits), expected);
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "window_bits must be in 1..=8")]
+fn test_pow_windowed_too_wide() {
+    Mod101::new(2).pow_windowed(10, 9);
+}
+
+#[test]
+fn test_inverse_strategy_for_modulus() {
+    let small_prime = if cfg!(feature = "egcd") { InvStrategy::ExtendedGcd } else { InvStrategy::Fermat };
+    assert_eq!(Mod101::inverse_strategy_for_modulus(), small_prime);
+    assert_eq!(Mod65521::inverse_strategy_for_modulus(), small_prime);
+    assert_eq!(Mod998244353::inverse_strategy_for_modulus(), InvStrategy::ExtendedGcd);
+    // 2^89 - 3 has 88 bits set, and products overflow u128
+    assert_eq!(Mod2p89::inverse_strategy_for_modulus(), InvStrategy::ExtendedGcd);
+    assert_eq!(WidthU32::inverse_strategy_for_modulus(), InvStrategy::ExtendedGcd);
+    assert_eq!(Mod1024::inverse_strategy_for_modulus(), InvStrategy::Newton);
+    assert_eq!(Mod100::inverse_strategy_for_modulus(), InvStrategy::ExtendedGcd);
+    assert_eq!(Mod15::inverse_strategy_for_modulus(), InvStrategy::ExtendedGcd);
+    for i in 0..100_u16 {
+        assert_eq!(Mod100::from(i).inv(), Mod100::from(i).brute_force_inv());
+    }
+}
+
+#[test]
+fn test_prime_inv_rejects_composite_and_zero() {
+    for i in 0..100_u16 {
+        assert_eq!(Mod100::from(i).prime_inv(), None);
+    }
+    assert_eq!(Mod15::new(2).prime_inv(), None);
+    assert_eq!(Mod101::new(0).prime_inv(), None);
+    assert_eq!(Mod101::new(2).prime_inv(), Some(Mod101::new(51)));
+}
+
+#[test]
+#[should_panic(expected = "prime_inv_unwrap called on zero or with composite modulus")]
+fn test_prime_inv_unwrap_panics_on_composite() {
+    let _ = Mod100::new(3).prime_inv_unwrap();
+}
+
+#[test]
+fn test_inv_strategies_agree_on_primes() {
+    for i in (1..65521_u32).step_by(7) {
+        let x = Mod65521::from(i);
+        assert_eq!(x.inv(), x.prime_inv());
+        assert_eq!(x.inv(), x.coprime_inv());
+    }
+    for i in 1..1000_i128 {
+        let x = Mod2p89::new(i * 1_000_000_007);
+        assert_eq!(x.inv(), x.prime_inv());
+        assert_eq!(x.inv().map(|inv| inv * x), Some(Mod2p89::new(1)));
+        let x = WidthU32::from_u128(i as u128 * 7919);
+        assert_eq!(x.inv(), x.prime_inv());
+    }
+}
+
+#[test]
+fn test_balanced_le_bytes() {
+    assert_eq!(Mod101::new(-1).to_balanced_le_bytes(), (-1_i16).to_le_bytes());
+    assert_eq!(Mod101::new(50).to_balanced_le_bytes(), [50, 0]);
+    assert_eq!(Mod101::from_balanced_le_bytes([0xff, 0xff]), Mod101::new(100));
+    for i in 0..101_u16 {
+        let x = Mod101::from(i);
+        assert_eq!(Mod101::from_balanced_le_bytes(x.to_balanced_le_bytes()), x);
+    }
+    for i in -100..100_i128 {
+        let x = Mod2p89::new(i * 1_000_000_007);
+        assert_eq!(Mod2p89::from_balanced_le_bytes(x.to_balanced_le_bytes()), x);
+    }
+}
+
+#[test]
+fn test_to_f64() {
+    assert_eq!(Mod101::new(0).to_f64_representative(), 0.0);
+    assert_eq!(Mod101::new(7).to_f64_representative(), 7.0);
+    assert_eq!(Mod101::new(-1).to_f64_representative(), 100.0);
+    assert_eq!(Mod101::new(7).to_f64_balanced(), 7.0);
+    assert_eq!(Mod101::new(-1).to_f64_balanced(), -1.0);
+    assert_eq!(Mod101::new(51).to_f64_balanced(), -50.0);
+    assert_eq!(Mod2p89::new(-2).to_f64_balanced(), -2.0);
+    assert_eq!(Mod2p89::new(-2).to_f64_representative(), 2_f64.powi(89));
+}
+
+#[test]
+fn test_to_balanced_slice() {
+    let items = [Mod101::new(0), Mod101::new(1), Mod101::new(50), Mod101::new(51), Mod101::new(100)];
+    assert_eq!(Mod101::to_balanced_slice(&items), [0, 1, 50, -50, -1]);
+    // for an even modulus, MOD / 2 is kept positive
+    let items = [Mod100::new(49), Mod100::new(50), Mod100::new(51), Mod100::new(99)];
+    assert_eq!(Mod100::to_balanced_slice(&items), [49, 50, -49, -1]);
+    assert_eq!(Mod2p89::new(-3).signed_value(), -3);
+    assert_eq!(Mod101::to_balanced_slice(&[]), []);
+}
+
+// (base, exp, base^exp mod MOD) triples computed independently with Python's built-in `pow`
+const POW_TABLE_2P61: [(u64, u128, u64); 66] = [
+    (0, 0, 1),
+    (0, 1, 0),
+    (0, 2, 0),
+    (0, 3, 0),
+    (0, 64, 0),
+    (0, 2305843009213693949, 0),
+    (0, 2305843009213693950, 0),
+    (0, 2305843009213693951, 0),
+    (0, 18446744073709551615, 0),
+    (0, 170141183460469231731687303715884105729, 0),
+    (0, 340282366920938463463374607431768211455, 0),
+    (1, 0, 1),
+    (1, 1, 1),
+    (1, 2, 1),
+    (1, 3, 1),
+    (1, 64, 1),
+    (1, 2305843009213693949, 1),
+    (1, 2305843009213693950, 1),
+    (1, 2305843009213693951, 1),
+    (1, 18446744073709551615, 1),
+    (1, 170141183460469231731687303715884105729, 1),
+    (1, 340282366920938463463374607431768211455, 1),
+    (2, 0, 1),
+    (2, 1, 2),
+    (2, 2, 4),
+    (2, 3, 8),
+    (2, 64, 8),
+    (2, 2305843009213693949, 1152921504606846976),
+    (2, 2305843009213693950, 1),
+    (2, 2305843009213693951, 2),
+    (2, 18446744073709551615, 32768),
+    (2, 170141183460469231731687303715884105729, 128),
+    (2, 340282366920938463463374607431768211455, 2048),
+    (3, 0, 1),
+    (3, 1, 3),
+    (3, 2, 9),
+    (3, 3, 27),
+    (3, 64, 1815558573193967996),
+    (3, 2305843009213693949, 1537228672809129301),
+    (3, 2305843009213693950, 1),
+    (3, 2305843009213693951, 3),
+    (3, 18446744073709551615, 14348907),
+    (3, 170141183460469231731687303715884105729, 1585360527008200975),
+    (3, 340282366920938463463374607431768211455, 255912711653570197),
+    (2305843009213693950, 0, 1),
+    (2305843009213693950, 1, 2305843009213693950),
+    (2305843009213693950, 2, 1),
+    (2305843009213693950, 3, 2305843009213693950),
+    (2305843009213693950, 64, 1),
+    (2305843009213693950, 2305843009213693949, 2305843009213693950),
+    (2305843009213693950, 2305843009213693950, 1),
+    (2305843009213693950, 2305843009213693951, 2305843009213693950),
+    (2305843009213693950, 18446744073709551615, 2305843009213693950),
+    (2305843009213693950, 170141183460469231731687303715884105729, 2305843009213693950),
+    (2305843009213693950, 340282366920938463463374607431768211455, 2305843009213693950),
+    (123456789012345678, 0, 1),
+    (123456789012345678, 1, 123456789012345678),
+    (123456789012345678, 2, 158193710583526553),
+    (123456789012345678, 3, 777360731239071749),
+    (123456789012345678, 64, 149674203127808171),
+    (123456789012345678, 2305843009213693949, 1884067921235876792),
+    (123456789012345678, 2305843009213693950, 1),
+    (123456789012345678, 2305843009213693951, 123456789012345678),
+    (123456789012345678, 18446744073709551615, 46921739491941022),
+    (123456789012345678, 170141183460469231731687303715884105729, 834606935334515839),
+    (123456789012345678, 340282366920938463463374607431768211455, 1836757244953163759),
+];
+
+const POW_TABLE_2P89: [(u128, u128, u128); 45] = [
+    (0, 0, 1),
+    (0, 1, 0),
+    (0, 2, 0),
+    (0, 97, 0),
+    (0, 618970019642690137449562109, 0),
+    (0, 618970019642690137449562110, 0),
+    (0, 618970019642690137449562111, 0),
+    (0, 1267650600228229401496703205383, 0),
+    (0, 340282366920938463463374607431768211455, 0),
+    (2, 0, 1),
+    (2, 1, 2),
+    (2, 2, 4),
+    (2, 97, 256),
+    (2, 618970019642690137449562109, 309485009821345068724781056),
+    (2, 618970019642690137449562110, 1),
+    (2, 618970019642690137449562111, 2),
+    (2, 1267650600228229401496703205383, 512),
+    (2, 340282366920938463463374607431768211455, 274877906944),
+    (5, 0, 1),
+    (5, 1, 5),
+    (5, 2, 25),
+    (5, 97, 381850511885250259253209548),
+    (5, 618970019642690137449562109, 495176015714152109959649689),
+    (5, 618970019642690137449562110, 1),
+    (5, 618970019642690137449562111, 5),
+    (5, 1267650600228229401496703205383, 32983371311141435197171162),
+    (5, 340282366920938463463374607431768211455, 255139350439287769796717479),
+    (618970019642690137449562110, 0, 1),
+    (618970019642690137449562110, 1, 618970019642690137449562110),
+    (618970019642690137449562110, 2, 1),
+    (618970019642690137449562110, 97, 618970019642690137449562110),
+    (618970019642690137449562110, 618970019642690137449562109, 618970019642690137449562110),
+    (618970019642690137449562110, 618970019642690137449562110, 1),
+    (618970019642690137449562110, 618970019642690137449562111, 618970019642690137449562110),
+    (618970019642690137449562110, 1267650600228229401496703205383, 618970019642690137449562110),
+    (618970019642690137449562110, 340282366920938463463374607431768211455, 618970019642690137449562110),
+    (98765432109876543210987654, 0, 1),
+    (98765432109876543210987654, 1, 98765432109876543210987654),
+    (98765432109876543210987654, 2, 480665213173889183378612600),
+    (98765432109876543210987654, 97, 434917252335700425858223830),
+    (98765432109876543210987654, 618970019642690137449562109, 114142993361299022201549900),
+    (98765432109876543210987654, 618970019642690137449562110, 1),
+    (98765432109876543210987654, 618970019642690137449562111, 98765432109876543210987654),
+    (98765432109876543210987654, 1267650600228229401496703205383, 325819525241837306368978860),
+    (98765432109876543210987654, 340282366920938463463374607431768211455, 70295283772789397713265442),
+];
+
+const POW_TABLE_100: [(u16, u128, u16); 40] = [
+    (0, 0, 1),
+    (0, 1, 0),
+    (0, 2, 0),
+    (0, 39, 0),
+    (0, 40, 0),
+    (0, 41, 0),
+    (0, 18446744073709551616, 0),
+    (0, 340282366920938463463374607431768211455, 0),
+    (2, 0, 1),
+    (2, 1, 2),
+    (2, 2, 4),
+    (2, 39, 88),
+    (2, 40, 76),
+    (2, 41, 52),
+    (2, 18446744073709551616, 36),
+    (2, 340282366920938463463374607431768211455, 68),
+    (3, 0, 1),
+    (3, 1, 3),
+    (3, 2, 9),
+    (3, 39, 67),
+    (3, 40, 1),
+    (3, 41, 3),
+    (3, 18446744073709551616, 21),
+    (3, 340282366920938463463374607431768211455, 7),
+    (10, 0, 1),
+    (10, 1, 10),
+    (10, 2, 0),
+    (10, 39, 0),
+    (10, 40, 0),
+    (10, 41, 0),
+    (10, 18446744073709551616, 0),
+    (10, 340282366920938463463374607431768211455, 0),
+    (99, 0, 1),
+    (99, 1, 99),
+    (99, 2, 1),
+    (99, 39, 99),
+    (99, 40, 1),
+    (99, 41, 99),
+    (99, 18446744073709551616, 1),
+    (99, 340282366920938463463374607431768211455, 99),
+];
+
+#[test]
+fn test_pow_reference_table() {
+    for &(base, exp, expected) in &POW_TABLE_2P61 {
+        assert_eq!(WidthU64::from(base).pow_u128(exp), WidthU64::from(expected), "{}^{}", base, exp);
+    }
+    for &(base, exp, expected) in &POW_TABLE_2P89 {
+        assert_eq!(Mod2p89::from(base).pow_u128(exp), Mod2p89::from(expected), "{}^{}", base, exp);
+    }
+    for &(base, exp, expected) in &POW_TABLE_100 {
+        assert_eq!(Mod100::from(base).pow_u128(exp), Mod100::from(expected), "{}^{}", base, exp);
+        if let Ok(exp) = usize::try_from(exp) {
+            assert_eq!(Mod100::from(base).pow(exp), Mod100::from(expected), "{}^{}", base, exp);
+        }
+    }
+}
+
+#[test]
+fn test_montgomery_slice_round_trip() {
+    let items = mod101_vec(&[0, 1, 2, 50, 100]);
+    let montgomery = Mod101::to_montgomery_slice(&items);
+    assert_eq!(Mod101::from_montgomery_slice(&montgomery), items);
+
+    let items: Vec<_> = (0..1000_i128).map(|i| Mod2p64m59::new(-i * 1_000_000_007)).collect();
+    assert_eq!(Mod2p64m59::from_montgomery_slice(&Mod2p64m59::to_montgomery_slice(&items)), items);
+    assert_eq!(WidthU64::from_montgomery_slice(&[]), []);
+}
+
+#[test]
+fn test_montgomery_arithmetic() {
+    let values: Vec<_> = (0..50_i128).map(|i| Mod2p64m59::new(-i * i * 12345 + 7)).collect();
+    for &a in &values {
+        for &b in &values {
+            let (x, y) = (Montgomery::from_modular(a), Montgomery::from_modular(b));
+            assert_eq!((x * y).to_modular(), a * b);
+            assert_eq!((x + y).to_modular(), a + b);
+            assert_eq!((x - y).to_modular(), a - b);
+        }
+    }
+    let x = Montgomery::from_modular(WidthU64::new(3));
+    assert_eq!((x * x * x).to_modular(), WidthU64::new(27));
+}
+
+#[test]
+fn test_to_usize() {
+    let mut counts = [0_u32; 101];
+    for i in 0..1010_u16 {
+        counts[Mod101::from(i).to_usize()] += 1;
+    }
+    assert!(counts.iter().all(|&c| c == 10));
+    assert_eq!(Mod2p89::new(-1).pow(0).to_usize(), 1);
+}
+
+#[test]
+fn test_group_order_divisors() {
+    assert_eq!(Mod101::group_order_divisors(), [1, 2, 4, 5, 10, 20, 25, 50, 100]);
+    assert_eq!(Mod103::group_order_divisors(), [1, 2, 3, 6, 17, 34, 51, 102]);
+    // 65520 = 2^4 * 3^2 * 5 * 7 * 13 has 5 * 3 * 2 * 2 * 2 divisors
+    assert_eq!(Mod65521::group_order_divisors().len(), 120);
+    assert!(Mod65521::group_order_divisors().iter().all(|&d| 65520 % d == 0));
+}
+
+#[test]
+fn test_order() {
+    for i in 1..101_u16 {
+        let x = Mod101::from(i);
+        let order = x.order().unwrap();
+        assert_eq!(x.pow(order as usize), Mod101::new(1));
+        assert_eq!((1..order).find(|&k| x.pow(k as usize) == Mod101::new(1)), None);
+        assert_eq!(order == 100, x.is_primitive_root());
+    }
+    assert_eq!(Mod101::new(1).order(), Some(1));
+    assert_eq!(Mod101::new(-1).order(), Some(2));
+    assert_eq!(Mod101::new(0).order(), None);
+    assert_eq!(Mod100::new(3).order(), None);
+    assert_eq!(WidthU64::new(-1).order(), Some(2));
+}
+
+#[test]
+fn test_group_order_factors() {
+    assert_eq!(Mod101::group_order_factors(), [2, 5]);
+    assert_eq!(Mod65521::group_order_factors(), [2, 3, 5, 7, 13]);
+    assert_eq!(Mod103::group_order_factors(), [2, 3, 17]);
+    assert!(core::ptr::eq(Mod101::group_order_factors(), Mod101::group_order_factors()));
+    assert_eq!(Mod101::primitive_root(), Some(Mod101::new(2)));
+    assert!(Mod65521::new(17).is_primitive_root());
+    assert!(!Mod65521::new(4).is_primitive_root());
+}
+
+#[test]
+fn test_neg() {
+    let x = Mod101::new(3);
+    assert_eq!(-x, Mod101::new(98));
+    assert_eq!(-&x, Mod101::new(98));
+    assert_eq!(-Mod101::new(0), Mod101::new(0));
+    assert_eq!(-(-x), x);
+    assert_eq!(-Mod2p89::new(1), Mod2p89::new(-1));
+}
+
+#[test]
+fn test_assign_ops() {
+    let mut x = Mod101::new(50);
+    x += Mod101::new(60);
+    assert_eq!(x, Mod101::new(9));
+    x -= Mod101::new(10);
+    assert_eq!(x, Mod101::new(100));
+    x *= Mod101::new(100);
+    assert_eq!(x, Mod101::new(1));
+    x /= Mod101::new(2);
+    assert_eq!(x, Mod101::new(51));
+}
+
+#[test]
+fn test_from_u128_reduced_full_range() {
+    let values = [0, 1, 100, 101, u64::MAX as u128, i128::MAX as u128, i128::MAX as u128 + 1, u128::MAX - 1, u128::MAX];
+    for &x in &values {
+        assert_eq!(Mod101::from_u128_reduced(x).remainder() as u128, x % 101);
+        assert_eq!(Mod63::from_u128_reduced(x).remainder() as u128, x % 63);
+        assert_eq!(Mod2p89::from_u128_reduced(x).remainder(), x % Mod2p89::MOD);
+        assert_eq!(ModMaxU128::from_u128_reduced(x).remainder(), x % ModMaxU128::MOD);
+        assert_eq!(ModMaxU128::from_u128_reduced(x), ModMaxU128::from_u128(x));
+    }
+    for shift in 0..128 {
+        let x = 1_u128 << shift;
+        assert_eq!(WidthU64::from_u128_reduced(x), WidthU64::new(2).pow(shift));
+    }
+}
+
+#[test]
+fn test_inv_pair() {
+    for a in 0..101_u16 {
+        let (a, b) = (Mod101::from(a), Mod101::from(a * 7 + 3));
+        let expected = match (a.inv(), b.inv()) {
+            (Some(x), Some(y)) => Some((x, y)),
+            _ => None,
+        };
+        assert_eq!(Mod101::inv_pair(a, b), expected);
+    }
+    assert_eq!(Mod100::inv_pair(Mod100::new(3), Mod100::new(7)), Some((Mod100::new(67), Mod100::new(43))));
+    assert_eq!(Mod100::inv_pair(Mod100::new(3), Mod100::new(5)), None);
+}
+
+#[test]
+fn test_crt_combine() {
+    for x in (0..9797_u16).step_by(37) {
+        assert_eq!(crt_combine(Mod101::from(x), Mod97::from(x)), Some((x as u128, 9797)));
+    }
+    assert_eq!(crt_combine(Mod15::new(4), Mod100::new(9)), None);
+    // the product of the moduli overflows u128
+    assert_eq!(crt_combine(Mod2p89::new(-1), WidthU64::new(5)), None);
+    let (value, modulus) = crt_combine(Mod65521::new(-1), Mod65519::new(5)).unwrap();
+    assert_eq!((value % 65521, value % 65519, modulus), (65520, 5, 65521 * 65519));
+}
+
+#[test]
+fn test_debug_assert_unit() {
+    Mod101::new(3).debug_assert_unit();
+    Mod100::new(3).debug_assert_unit();
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "Mod100(10) is not a unit modulo 100")]
+fn test_debug_assert_unit_panics() {
+    Mod100::new(10).debug_assert_unit();
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_memoized_pow() {
+    crate::clear_memoized_pow_cache();
+    for &n in &[0_u128, 1, 5, 100, u128::MAX] {
+        let x = WidthU64::new(12345);
+        assert_eq!(x.memoized_pow(n), x.pow_u128(n));
+    }
+    assert_eq!(crate::memoized_pow_cache_len(), 5);
+    // a cache hit returns the same value without adding an entry
+    assert_eq!(WidthU64::new(12345).memoized_pow(100), WidthU64::new(12345).pow(100));
+    assert_eq!(crate::memoized_pow_cache_len(), 5);
+    // the same representative under another modulus is cached separately
+    assert_eq!(Mod101::new(45).memoized_pow(100), Mod101::new(45).pow(100));
+    assert_eq!(crate::memoized_pow_cache_len(), 6);
+    crate::clear_memoized_pow_cache();
+    assert_eq!(crate::memoized_pow_cache_len(), 0);
+}
+
+// the arithmetic stays canonical modulo 1, but debug builds reject the modulus
+#[test]
+#[cfg(not(debug_assertions))]
+fn test_modulus_one() {
+    let zero = Mod1::from_signed(0);
+    assert_eq!(Mod1::from_signed(5), zero);
+    assert_eq!(Mod1::ZERO, zero);
+    assert_eq!(Mod1::from_signed(1).pow(0), zero);
+    // in the degenerate ring, zero is one, so both conventions agree
+    assert_eq!(zero.pow_with_zero_convention(0, zero), zero);
+    assert_eq!(<Mod1 as crate::One>::one(), zero);
+    assert_eq!(Mod1::from_signed(3).pow(0), zero);
+    assert_eq!(Mod1::from_signed(3).pow_u128(u128::MAX), zero);
+    assert_eq!(Mod1::from_signed(3) + Mod1::from_signed(4), zero);
+    assert_eq!(Mod1::from_signed(3) - Mod1::from_signed(4), zero);
+    assert_eq!(Mod1::from_signed(3) * Mod1::from_signed(4), zero);
+    assert_eq!(zero.halve(), Some(zero));
+    assert_eq!(Mod1::power_tower(&[2, 3]), zero);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "modular type with the degenerate modulus 1")]
+fn test_modulus_one_debug_assertion_constructor() {
+    Mod1::from_u128(3);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "modular type with the degenerate modulus 1")]
+fn test_modulus_one_debug_assertion_operator() {
+    let _ = Mod1::ZERO * Mod1::ZERO;
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "modular type with the degenerate modulus 1")]
+fn test_modulus_one_debug_assertion_pow() {
+    Mod1::ZERO.pow_u128(5);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "pow_mod_u128 called with degenerate modulus 1")]
+fn test_pow_mod_u128_degenerate_modulus() {
+    crate::pow_mod_u128(3, 5, 1);
+}
+
+#[test]
+fn test_reinterpret_from() {
+    assert_eq!(Mod101::reinterpret_from(Mod107::new(105)), Mod101::new(4));
+    assert_eq!(Mod107::reinterpret_from(Mod101::new(100)), Mod107::new(100));
+    assert_eq!(Mod15::reinterpret_from(Mod2p89::new(-1)), Mod15::from_u128(Mod2p89::MOD - 1));
+    // 256 divides 1024, so reducing from Mod1024 is compatible with the arithmetic
+    let (a, b) = (Mod1024::new(700), Mod1024::new(999));
+    assert_eq!(Mod256::reinterpret_from(a * b), Mod256::reinterpret_from(a) * Mod256::reinterpret_from(b));
+    // but 101 does not divide 107
+    let (a, b) = (Mod107::new(100), Mod107::new(10));
+    assert_ne!(Mod101::reinterpret_from(a + b), Mod101::reinterpret_from(a) + Mod101::reinterpret_from(b));
+}
+
+// reinterpret_from only reduces representatives, so it does not preserve the arithmetic of the
+// source modulus unless the target modulus divides it
+#[test]
+fn test_reinterpret_from_is_not_homomorphic() {
+    let convert = Mod101::reinterpret_from::<Mod107>;
+    let (a, b) = (Mod107::new(100), Mod107::new(10));
+    // 110 wraps to 3 modulo 107
+    assert_eq!(convert(a + b), Mod101::new(3));
+    assert_eq!(convert(a) + convert(b), Mod101::new(9));
+    // 1000 wraps to 37 modulo 107
+    assert_eq!(convert(a * b), Mod101::new(37));
+    assert_eq!(convert(a) * convert(b), Mod101::new(91));
+    // -1 is 106 modulo 107, which is 5 modulo 101
+    assert_eq!(convert(-Mod107::new(1)), Mod101::new(5));
+    assert_eq!(-convert(Mod107::new(1)), Mod101::new(100));
+    // without wrapping around 107, the results agree
+    let (a, b) = (Mod107::new(50), Mod107::new(2));
+    assert_eq!(convert(a * b), convert(a) * convert(b));
+}
+
+#[test]
+fn test_sum_raw_checked() {
+    // 1000 * 65535 and 1000 * 255 overflow the base types
+    assert_eq!(Mod101::sum_raw_checked(core::iter::repeat_n(u16::MAX, 1000)), Mod101::from_u128(1000 * 65535));
+    assert_eq!(Mod15::sum_raw_checked(core::iter::repeat_n(255_u8, 1000)), Mod15::from_u128(255_000));
+    assert_eq!(Mod101::sum_raw_checked(Vec::new()), Mod101::new(0));
+    // the accumulator itself overflows u128 here
+    let expected = Mod2p89::from_u128(u128::MAX) * Mod2p89::new(10);
+    assert_eq!(Mod2p89::sum_raw_checked(core::iter::repeat_n(u128::MAX, 10)), expected);
+}
+
+#[test]
+fn test_convolve_small() {
+    let a: Vec<_> = [1, 2, 3].iter().map(|&x| Mod101::new(x)).collect();
+    let b: Vec<_> = [4, 5].iter().map(|&x| Mod101::new(x)).collect();
+    let expected: Vec<_> = [4, 13, 22, 15].iter().map(|&x| Mod101::new(x)).collect();
+    assert_eq!(Mod101::convolve(&a, &b), expected);
+    assert_eq!(Mod101::convolve(&a, &[]), Vec::new());
+    assert_eq!(Mod101::convolve(&[], &[]), Vec::new());
+}
+
+#[test]
+fn test_convolve_ntt_matches_schoolbook() {
+    for &(n, m) in &[(32, 32), (100, 37), (1, 500), (257, 256), (40, 1000)] {
+        let a: Vec<_> = (0..n).map(|i| Mod998244353::new(i * i * 7919 - 12345)).collect();
+        let b: Vec<_> = (0..m).map(|i| Mod998244353::new(i * 104_729 + 3)).collect();
+        let expected = crate::convolve_schoolbook(&a, &b);
+        assert_eq!(Mod998244353::convolve(&a, &b), expected);
+
+        let size = (a.len() + b.len() - 1).next_power_of_two() as u128;
+        let root = Mod998244353::cached_primitive_root().unwrap().pow_u128((Mod998244353::MOD as u128 - 1) / size);
+        assert_eq!(crate::convolve_ntt(&a, &b, root), expected);
+    }
+}
+
+#[test]
+fn test_convolve_falls_back_without_roots_of_unity() {
+    // 65520 is only divisible by 2^4, and 100 is composite
+    let a: Vec<_> = (0..50).map(|i| Mod65521::new(i * 1000 + 1)).collect();
+    assert_eq!(Mod65521::convolve(&a, &a), crate::convolve_schoolbook(&a, &a));
+    let a: Vec<_> = (0..50).map(|i| Mod100::new(i * 3 + 1)).collect();
+    assert_eq!(Mod100::convolve(&a, &a), crate::convolve_schoolbook(&a, &a));
+}
+
+#[test]
+fn test_convolve_arbitrary() {
+    // 10^9 + 7 has no power-of-two root of unity beyond order 2
+    let a: Vec<_> = (0..300).map(|i| -WidthU32::from_u128(i * 7_654_321 + 1)).collect();
+    let b: Vec<_> = (0..200).map(|i| WidthU32::from_u128(i * i * 1_234_567)).collect();
+    assert_eq!(WidthU32::convolve_arbitrary(&a, &b), crate::convolve_schoolbook(&a, &b));
+    let a: Vec<_> = (0..100).map(|i| Mod100::new(i * 37 + 1)).collect();
+    assert_eq!(Mod100::convolve_arbitrary(&a, &a), crate::convolve_schoolbook(&a, &a));
+    assert_eq!(WidthU32::convolve_arbitrary(&[], &b), Vec::new());
+    // the coefficients would exceed the product of the primes, so this falls back to schoolbook
+    let a: Vec<_> = (0..40).map(|i| Mod2p89::new(-i - 1)).collect();
+    assert_eq!(Mod2p89::convolve_arbitrary(&a, &a), crate::convolve_schoolbook(&a, &a));
+}
+
+#[test]
+fn test_pow_signed_exponent() {
+    use crate::Pow;
+    let x = Mod101::new(3);
+    assert_eq!(Pow::pow(x, 4_i64), Some(Mod101::new(81)));
+    assert_eq!(Pow::pow(x, 0_i64), Some(Mod101::new(1)));
+    // 3 * 34 = 102
+    assert_eq!(Pow::pow(x, -1_i64), Some(Mod101::new(34)));
+    assert_eq!(Pow::pow(x, -3_i64), Some(Mod101::new(34).pow_u128(3)));
+    assert_eq!(Pow::pow(x, -3_i64).map(|y| y * x.pow_u128(3)), Some(Mod101::new(1)));
+    assert_eq!(Pow::pow(x, i64::MIN), x.inv().map(|inv| inv.pow_u128(1 << 63)));
+    assert_eq!(Pow::pow(Mod101::new(0), -1_i64), None);
+    assert_eq!(Pow::pow(Mod100::new(10), 2_i64), Some(Mod100::new(0)));
+    assert_eq!(Pow::pow(Mod100::new(10), -2_i64), None);
+    assert_eq!(Pow::pow(Mod100::new(3), -1_i64), Some(Mod100::new(67)));
+}
+
+#[cfg(feature = "checked")]
+#[test]
+fn test_checked_near_max_modulus() {
+    let x = Mod127::new(126);
+    assert_eq!(x + x, Mod127::new(125));
+    assert_eq!(x * x, Mod127::new(1));
+    assert_eq!(x - Mod127::new(127), x);
+    assert_eq!(Mod127::new(-1), x);
+    for i in 1..127_u8 {
+        let x = Mod127::from(i);
+        assert_eq!(x * x.inv().unwrap(), Mod127::new(1), "{:?}", x);
+        assert_eq!(x + (-x), Mod127::new(0));
+    }
+    assert_eq!(Mod127::new(3).pow(126), Mod127::new(1));
+}
+
+#[test]
+fn test_from_byte_array() {
+    assert_eq!(Mod101::from([5, 0]), Mod101::new(5));
+    // 0x0101 = 257 = 2 * 101 + 55
+    assert_eq!(Mod101::from([1, 1]), Mod101::new(55));
+    assert_eq!(Mod15::from([200]), Mod15::new(5));
+    assert_eq!(Mod65521::from([0xff; 4]), Mod65521::from(u32::MAX));
+    let digest = [0xab_u8; 16];
+    assert_eq!(Mod2p89::from(digest), Mod2p89::from_u128(u128::from_le_bytes(digest)));
+}
+
+#[test]
+fn test_pow_with_zero_convention() {
+    let zero = Mod101::new(0);
+    assert_eq!(zero.pow(0), Mod101::new(1));
+    assert_eq!(zero.pow_with_zero_convention(0, Mod101::new(1)), Mod101::new(1));
+    assert_eq!(zero.pow_with_zero_convention(0, Mod101::new(0)), Mod101::new(0));
+    assert_eq!(zero.pow_with_zero_convention(3, Mod101::new(1)), Mod101::new(0));
+    assert_eq!(Mod101::new(7).pow_with_zero_convention(0, Mod101::new(0)), Mod101::new(1));
+    assert_eq!(Mod101::new(7).pow_with_zero_convention(2, Mod101::new(0)), Mod101::new(49));
+}
+
+#[test]
+fn test_dft_matrix_matches_ntt() {
+    for &n in &[1, 2, 4, 8, 16] {
+        let matrix = Mod998244353::dft_matrix(n).unwrap();
+        assert_eq!((matrix.rows(), matrix.cols()), (n, n));
+        let root = Mod998244353::cached_primitive_root().unwrap().pow_u128((Mod998244353::MOD as u128 - 1) / n as u128);
+        let values: Vec<_> = (0..n as i64).map(|i| Mod998244353::new(i * i - 3 * i + 7)).collect();
+        let mut transformed = values.clone();
+        crate::ntt_in_place(&mut transformed, root);
+        assert_eq!(matrix.mul_vec(&values), transformed);
+    }
+    // 5 divides 100 = 101 - 1, and F * F is n times the reversal permutation
+    let matrix = Mod101::dft_matrix(5).unwrap();
+    let square = &matrix * &matrix;
+    let expected = Matrix::from_fn(5, 5, |i, j| if (i + j) % 5 == 0 { Mod101::new(5) } else { Mod101::new(0) });
+    assert_eq!(square, expected);
+    assert_eq!(Mod101::dft_matrix(3), None);
+    assert_eq!(Mod101::dft_matrix(0), None);
+    assert_eq!(Mod100::dft_matrix(2), None);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_cached_inv() {
+    // more distinct values than the capacity, each looked up twice to exercise hits and eviction
+    for round in 0..2 {
+        for i in 0..(crate::INV_CACHE_CAPACITY as u16 * 2) {
+            let x = Mod101::from(i);
+            assert_eq!(x.cached_inv(), x.inv(), "{:?} in round {}", x, round);
+        }
+    }
+    for _ in 0..3 {
+        assert_eq!(Mod100::new(10).cached_inv(), None);
+        assert_eq!(Mod100::new(3).cached_inv(), Some(Mod100::new(67)));
+    }
+    // the same representative under another modulus is cached separately
+    assert_eq!(Mod103::new(3).cached_inv(), Mod103::new(3).inv());
+    assert_eq!(Mod2p89::new(3).cached_inv(), Mod2p89::new(3).inv());
+}
+
+#[test]
+fn test_pow_split() {
+    for &n in &[0_u128, 1, 2, 3, 100, 12345, u64::MAX as u128, u128::MAX] {
+        for &parts in &[0, 1, 2, 3, 7, 64, 128, 1000] {
+            let x = Mod2p89::new(1_234_567_891);
+            assert_eq!(x.pow_split(n, parts), x.pow_u128(n), "n = {}, parts = {}", n, parts);
+            let x = Mod100::new(7);
+            assert_eq!(x.pow_split(n, parts), x.pow_u128(n), "n = {}, parts = {}", n, parts);
+        }
+    }
+    assert_eq!(Mod101::new(0).pow_split(0, 4), Mod101::new(1));
+}
+
+#[test]
+fn test_squarefree_factorization() {
+    let poly = |coeffs: &[i16]| Polynomial::from_coeffs(coeffs.iter().map(|&c| Mod101::new(c)).collect());
+    // (x - 1)^2 (x - 2) = x^3 - 4x^2 + 5x - 2
+    let f = poly(&[-2, 5, -4, 1]);
+    assert_eq!(f.squarefree_factorization(), vec![(poly(&[-2, 1]), 1), (poly(&[-1, 1]), 2)]);
+
+    // 3 (x + 1) (x - 3)^3 (x^2 + 1)^2, recombined up to the leading coefficient
+    let factors = [(poly(&[1, 1]), 1), (poly(&[-3, 1]), 3), (poly(&[1, 0, 1]), 2)];
+    let g = factors.iter().fold(poly(&[3]), |acc, (h, m)| (0..*m).fold(acc, |acc, _| &acc * h));
+    let result = g.squarefree_factorization();
+    assert_eq!(result.iter().map(|(_, m)| *m).collect::<Vec<_>>(), vec![1, 2, 3]);
+    let recombined = result.iter().fold(poly(&[1]), |acc, (h, m)| (0..*m).fold(acc, |acc, _| &acc * h));
+    assert_eq!(recombined, g.monic());
+
+    // x^5 + 1 = (x + 1)^5 in characteristic 5 has a zero derivative
+    let f5 = Polynomial::from_coeffs(vec![Mod5::new(1), Mod5::new(0), Mod5::new(0), Mod5::new(0), Mod5::new(0), Mod5::new(1)]);
+    assert_eq!(f5.squarefree_factorization(), vec![(Polynomial::from_coeffs(vec![Mod5::new(1), Mod5::new(1)]), 5)]);
+
+    assert!(poly(&[7]).squarefree_factorization().is_empty());
+    assert!(poly(&[]).squarefree_factorization().is_empty());
+}
+
+#[test]
+fn test_polynomial_roots() {
+    let linear_product = |roots: &[i64]| roots.iter().fold(Polynomial::from_coeffs(vec![Mod998244353::new(1)]), |acc, &r| {
+        &acc * &Polynomial::from_coeffs(vec![Mod998244353::new(-r), Mod998244353::new(1)])
+    });
+    // (x - 3)(x - 5)(x - 77)(x + 1) (x^2 + 2), where -2 is a non-residue modulo 101
+    let poly = |coeffs: &[i16]| Polynomial::from_coeffs(coeffs.iter().map(|&c| Mod101::new(c)).collect());
+    let f = [poly(&[-3, 1]), poly(&[-5, 1]), poly(&[-77, 1]), poly(&[1, 1]), poly(&[2, 0, 1])]
+        .iter().fold(poly(&[1]), |acc, h| &acc * h);
+    assert_eq!(Mod101::new(-2).legendre(), Some(-1));
+    let roots = f.roots();
+    assert_eq!(roots, vec![Mod101::new(3), Mod101::new(5), Mod101::new(77), Mod101::new(100)]);
+    assert!(roots.iter().all(|&r| f.eval(r).is_zero()));
+
+    // the splitting path, with a repeated root and a root at zero
+    let g = linear_product(&[0, 2, 2, 123_456_789, -1, 998_244_352 / 2]);
+    let roots = g.roots();
+    assert_eq!(roots, vec![Mod998244353::new(0), Mod998244353::new(2), Mod998244353::new(123_456_789),
+                           Mod998244353::new(499_122_176), Mod998244353::new(-1)]);
+    assert!(roots.iter().all(|&r| g.eval(r).is_zero()));
+    let irreducible = Polynomial::from_coeffs(vec![Mod998244353::new(3), Mod998244353::new(0), Mod998244353::new(1)]);
+    assert_eq!(Mod998244353::new(-3).legendre(), Some(-1));
+    assert!(irreducible.roots().is_empty());
+    assert!(poly(&[4]).roots().is_empty());
+}
+
+#[test]
+fn test_pow_via_log() {
+    let tables = Mod101::log_tables().unwrap();
+    assert_eq!(tables.0.len(), 100);
+    for x in 1..101_u16 {
+        let x = Mod101::from(x);
+        for &n in &[0, 1, 2, 3, 50, 99, 100, 101, 12345, usize::MAX] {
+            assert_eq!(x.pow_via_log(n, &tables), x.pow(n), "{:?}^{}", x, n);
+        }
+    }
+    assert_eq!(Mod101::new(0).pow_via_log(0, &tables), Mod101::new(1));
+    assert_eq!(Mod101::new(0).pow_via_log(5, &tables), Mod101::new(0));
+    assert_eq!(Mod100::log_tables(), None);
+}
+
+#[test]
+fn test_is_zero_divisor() {
+    assert!((0..101_u16).all(|x| !Mod101::from(x).is_zero_divisor()));
+    assert!(!Mod100::new(0).is_zero_divisor());
+    assert!(!Mod100::new(3).is_zero_divisor());
+    assert!(Mod100::new(2).is_zero_divisor());
+    assert!(Mod100::new(25).is_zero_divisor());
+    assert_eq!(Mod100::new(25) * Mod100::new(4), Mod100::new(0));
+    assert_eq!((1..15_u8).filter(|&x| Mod15::from(x).is_zero_divisor()).collect::<Vec<_>>(), vec![3, 5, 6, 9, 10, 12]);
+    assert!(Mod256::new(128).is_zero_divisor());
+    assert!(!Mod256::new(127).is_zero_divisor());
+}
+
+#[test]
+fn test_nilpotency_index() {
+    // 121 = 11^2
+    assert_eq!(Mod121::new(0).nilpotency_index(), Some(1));
+    assert_eq!(Mod121::new(11).nilpotency_index(), Some(2));
+    assert_eq!(Mod121::new(55).nilpotency_index(), Some(2));
+    assert_eq!(Mod121::new(1).nilpotency_index(), None);
+    assert_eq!(Mod121::new(12).nilpotency_index(), None);
+    for x in 0..121_u16 {
+        let x = Mod121::from(x);
+        if let Some(m) = x.nilpotency_index() {
+            assert_eq!(x.pow(m), Mod121::new(0));
+            assert!(m == 1 || x.pow(m - 1) != Mod121::new(0));
+        }
+    }
+    assert_eq!(Mod1024::new(2).nilpotency_index(), Some(10));
+    assert_eq!(Mod1024::new(6).nilpotency_index(), Some(10));
+    assert_eq!(Mod1024::new(96).nilpotency_index(), Some(2));
+    assert_eq!(Mod100::new(10).nilpotency_index(), Some(2));
+    assert_eq!(Mod100::new(20).nilpotency_index(), Some(2));
+    assert_eq!(Mod100::new(2).nilpotency_index(), None);
+    assert_eq!(Mod101::new(7).nilpotency_index(), None);
+    assert_eq!(Mod15::new(0).nilpotency_index(), Some(1));
+}
+
+#[test]
+fn test_sum_rationals() {
+    assert_eq!(Mod101::sum_rationals(&[(1, 2), (1, 3), (1, 6)]), Some(Mod101::new(1)));
+    assert_eq!(Mod101::sum_rationals(&[(3, -4), (-5, 8), (7, 1)]), Mod101::from_rational(45, 8));
+    assert_eq!(Mod101::sum_rationals(&[]), Some(Mod101::new(0)));
+    assert_eq!(Mod101::sum_rationals(&[(1, 2), (1, 202)]), None);
+    assert_eq!(Mod100::sum_rationals(&[(1, 3), (1, 7)]), Mod100::from_rational(10, 21));
+    assert_eq!(Mod100::sum_rationals(&[(1, 3), (1, 5)]), None);
+}
+
+#[test]
+fn test_harmonic() {
+    let mut sum = Mod101::new(0);
+    for n in 1..101 {
+        sum += Mod101::new(1) / Mod101::new(n as i16);
+        assert_eq!(Mod101::harmonic(n), Some(sum), "n = {}", n);
+    }
+    assert_eq!(Mod101::harmonic(0), Some(Mod101::new(0)));
+    // H_3 = 11/6
+    assert_eq!(Mod101::harmonic(3), Mod101::from_rational(11, 6));
+    assert_eq!(Mod101::harmonic(101), None);
+    assert_eq!(Mod15::harmonic(2), Mod15::from_rational(3, 2));
+    assert_eq!(Mod15::harmonic(3), None);
+}
+
+#[test]
+fn test_binomials() {
+    let binomials = Binomials::<Mod101>::new(100).unwrap();
+    assert_eq!(binomials.limit(), 100);
+    assert_eq!(binomials.choose(5, 2), Mod101::new(10));
+    assert_eq!(binomials.choose(5, 6), Mod101::new(0));
+    assert_eq!(binomials.factorial(5), Mod101::new(19));
+    assert_eq!(binomials.factorial(7) * binomials.inv_factorial(7), Mod101::new(1));
+    for n in 1..=100 {
+        for k in 1..n {
+            assert_eq!(binomials.choose(n, k), binomials.choose(n - 1, k - 1) + binomials.choose(n - 1, k));
+        }
+    }
+    assert!(Binomials::<Mod101>::new(101).is_none());
+    assert!(Binomials::<Mod15>::new(2).is_some());
+    assert!(Binomials::<Mod15>::new(3).is_none());
+}
+
+#[test]
+fn test_catalan() {
+    const CATALAN: [u64; 16] = [1, 1, 2, 5, 14, 42, 132, 429, 1430, 4862, 16796, 58786, 208012, 742900, 2674440, 9694845];
+    for (n, &c) in CATALAN.iter().enumerate() {
+        assert_eq!(Mod998244353::catalan(n), Mod998244353::from(c), "n = {}", n);
+        assert_eq!(Mod101::catalan(n), Mod101::from((c % 101) as u16), "n = {}", n);
+        assert_eq!(Mod100::catalan(n), Mod100::from((c % 100) as u16), "n = {}", n);
+    }
+    // 2n >= 101 goes through the recurrence
+    let mut recurrence = vec![Mod101::new(1)];
+    for i in 0..60 {
+        let next = (0..=i).map(|j| recurrence[j] * recurrence[i - j]).sum();
+        recurrence.push(next);
+    }
+    for (n, &c) in recurrence.iter().enumerate() {
+        assert_eq!(Mod101::catalan(n), c, "n = {}", n);
+    }
+}
+
+#[test]
+fn test_bernoulli() {
+    const BERNOULLI: [(i128, i128); 13] = [(1, 1), (-1, 2), (1, 6), (0, 1), (-1, 30), (0, 1), (1, 42), (0, 1), (-1, 30), (0, 1),
+                                           (5, 66), (0, 1), (-691, 2730)];
+    for (n, &(num, den)) in BERNOULLI.iter().enumerate() {
+        assert_eq!(Mod101::bernoulli(n), Mod101::from_rational(num, den), "n = {}", n);
+        assert_eq!(Mod998244353::bernoulli(n), Mod998244353::from_rational(num, den), "n = {}", n);
+    }
+    assert!(Mod101::bernoulli(99).is_some());
+    assert_eq!(Mod101::bernoulli(100), None);
+    assert_eq!(Mod15::bernoulli(2), None);
+}
+
+#[test]
+fn test_stirling_second() {
+    let mut table = vec![vec![0_u64; 21]; 21];
+    table[0][0] = 1;
+    for n in 1..21 {
+        for k in 1..=n {
+            table[n][k] = k as u64 * table[n - 1][k] + table[n - 1][k - 1];
+        }
+    }
+    for (n, row) in table.iter().enumerate() {
+        for (k, &s) in row.iter().enumerate() {
+            assert_eq!(Mod998244353::stirling_second(n, k), Mod998244353::from(s), "S({}, {})", n, k);
+            assert_eq!(Mod101::stirling_second(n, k), Mod101::from((s % 101) as u16), "S({}, {})", n, k);
+            // 3! is not invertible modulo 15, so larger k use the recurrence
+            assert_eq!(Mod15::stirling_second(n, k), Mod15::from((s % 15) as u8), "S({}, {})", n, k);
+        }
+    }
+    assert_eq!(Mod101::stirling_second(3, 5), Mod101::new(0));
+}
+
+#[test]
+fn test_walsh_hadamard_convolutions() {
+    for k in 0..6 {
+        let n = 1 << k;
+        let a: Vec<_> = (0..n as i16).map(|i| Mod101::new(i * i + 3)).collect();
+        let b: Vec<_> = (0..n as i16).map(|i| Mod101::new(7 * i - 2)).collect();
+        let mut xor = vec![Mod101::new(0); n];
+        let mut or = vec![Mod101::new(0); n];
+        for i in 0..n {
+            for j in 0..n {
+                xor[i ^ j] += a[i] * b[j];
+                or[i | j] += a[i] * b[j];
+            }
+        }
+        assert_eq!(Mod101::xor_convolve(&a, &b), Some(xor), "k = {}", k);
+        assert_eq!(Mod101::or_convolve(&a, &b), or, "k = {}", k);
+
+        let mut transformed = a.clone();
+        Mod101::walsh_hadamard(&mut transformed, false);
+        Mod101::walsh_hadamard(&mut transformed, true);
+        assert_eq!(transformed, a);
+    }
+    let a: Vec<_> = (0..8_u16).map(Mod100::from).collect();
+    assert_eq!(Mod100::xor_convolve(&a, &a), None);
+    assert_eq!(Mod100::or_convolve(&a, &a)[0], Mod100::new(0));
+}
+
+#[test]
+#[should_panic(expected = "power of two")]
+fn test_walsh_hadamard_length() {
+    Mod101::walsh_hadamard(&mut [Mod101::new(1); 3], false);
+}
+
+#[test]
+fn test_mobius_and_num_divisors() {
+    let expected_mobius = [1, -1, -1, 0, -1, 1, -1, 0, 0, 1, -1, 0, -1, 1, 1, 0, -1, 0, -1, 0];
+    for (i, &mu) in expected_mobius.iter().enumerate() {
+        assert_eq!(mobius(i as u32 + 1), mu, "n = {}", i + 1);
+    }
+    for n in 1..500_u32 {
+        assert_eq!(num_divisors(n), (1..=n).filter(|d| n % d == 0).count(), "n = {}", n);
+        // the Möbius function sums to zero over the divisors of every n > 1
+        let sum: i32 = (1..=n).filter(|d| n % d == 0).map(|d| mobius(d) as i32).sum();
+        assert_eq!(sum, (n == 1) as i32, "n = {}", n);
+    }
+    // 998244352 = 2^23 * 7 * 17
+    assert_eq!(num_divisors(998_244_352_u64), 96);
+    assert_eq!(mobius(30_u8), -1);
+    assert_eq!(mobius(Mod101::MOD), -1);
+}
+
+#[test]
+fn test_smallest_prime_factor_sieve() {
+    let spf = smallest_prime_factor_sieve(10_000);
+    assert_eq!(spf.len(), 10_001);
+    assert_eq!(&spf[..10], &[0, 0, 2, 3, 2, 5, 2, 7, 2, 3]);
+    for n in 2..=10_000_usize {
+        let mut factors = Vec::new();
+        let mut m = n;
+        while m > 1 {
+            let p = spf[m];
+            if factors.last() != Some(&p) {
+                factors.push(p);
+            }
+            m /= p;
+        }
+        assert_eq!(factors, crate::list_prime_factors(n), "n = {}", n);
+    }
+    assert_eq!(smallest_prime_factor_sieve(0), vec![0]);
+    assert_eq!(smallest_prime_factor_sieve(1), vec![0, 0]);
+}
+
+#[test]
+fn test_is_primitive_root_mod_n() {
+    assert!(is_primitive_root(1_u32, 2));
+    assert!(is_primitive_root(3_u32, 4));
+    assert!(!is_primitive_root(1_u32, 4));
+    // 2 generates the units modulo 3^k and 2 * 3^k
+    assert!(is_primitive_root(2_u32, 9));
+    assert!(is_primitive_root(2_u32, 27));
+    assert!(is_primitive_root(5_u32, 18));
+    // 7 generates the units modulo 5 but not modulo 25
+    assert!(!is_primitive_root(7_u32, 25));
+    assert!(is_primitive_root(2_u32, 25));
+    for n in [8_u32, 12, 15, 16, 21, 100] {
+        assert!((0..n).all(|g| !is_primitive_root(g, n)), "n = {}", n);
+    }
+    // the free function agrees with the method for prime moduli
+    for g in 0..101_u16 {
+        assert_eq!(is_primitive_root(g, 101), Mod101::from(g).is_primitive_root(), "g = {}", g);
+    }
+    assert!(!is_primitive_root(3_u32, 0));
+    assert!(!is_primitive_root(2_u32, 4));
+}
+
+#[test]
+fn test_discrete_log() {
+    for b in 0..101_u16 {
+        for t in 0..101_u16 {
+            let (base, target) = (Mod101::from(b), Mod101::from(t));
+            let expected = (0..101).find(|&k| base.pow(k) == target);
+            assert_eq!(Mod101::discrete_log(base, target), expected, "log_{} {}", b, t);
+            assert_eq!(Mod101::discrete_log_ph(base, target), expected, "log_{} {}", b, t);
+        }
+    }
+    // 998244353 - 1 = 2^23 * 7 * 17 is smooth
+    let g = Mod998244353::new(3);
+    for &k in &[0_u64, 1, 2, 12345, 998_244_351, 499_122_176, 7 << 20] {
+        let target = g.pow_u128(k as u128);
+        assert_eq!(Mod998244353::discrete_log_ph(g, target), Some(k as usize));
+        assert_eq!(Mod998244353::discrete_log(g, target), Some(k as usize));
+    }
+    // 3^17 has order 2^23 * 7, so powers of 3 whose exponent is not a multiple of 17, such as g
+    // itself, are out of its subgroup
+    let h = g.pow(17);
+    assert_eq!(Mod998244353::discrete_log_ph(h, g.pow(17 * 1000)), Some(1000));
+    assert_eq!(Mod998244353::discrete_log_ph(h, g), None);
+    assert_eq!(Mod998244353::discrete_log(h, g), None);
+    assert_eq!(Mod100::discrete_log(Mod100::new(3), Mod100::new(9)), None);
+}
+
+#[test]
+fn test_from_raw() {
+    assert_eq!(Mod101::from_raw(42), Mod101::new(42));
+    assert_eq!(Mod101::from_raw(100) + Mod101::from_raw(1), Mod101::new(0));
+}
+
+#[test]
+#[cfg(feature = "verify")]
+#[should_panic(expected = "not a canonical representative")]
+fn test_verify_rejects_raw_out_of_range() {
+    let _ = Mod101::from_raw(150) + Mod101::new(1);
+}
+
+#[test]
+#[cfg(feature = "verify")]
+#[should_panic(expected = "not a canonical representative")]
+fn test_verify_rejects_raw_out_of_range_in_mul() {
+    let _ = Mod101::new(3) * Mod101::from_raw(101);
+}
+
+#[test]
+fn test_residue_bits() {
+    // 100 needs 7 bits
+    assert_eq!(Mod101::new(5).residue_bits(), vec![true, false, true, false, false, false, false]);
+    assert_eq!(Mod101::new(100).residue_bits().len(), 7);
+    for x in 0..101_u16 {
+        let x = Mod101::from(x);
+        assert_eq!(Mod101::from_residue_bits(&x.residue_bits()), x);
+    }
+    for x in 0..256_u16 {
+        let x = Mod256::from(x);
+        assert_eq!(x.residue_bits().len(), 8);
+        assert_eq!(Mod256::from_residue_bits(&x.residue_bits()), x);
+    }
+    let x = Mod2p89::new(-12345);
+    assert_eq!(x.residue_bits().len(), 89);
+    assert_eq!(Mod2p89::from_residue_bits(&x.residue_bits()), x);
+    // longer inputs are reduced: 0b1111111 = 127 = 101 + 26
+    assert_eq!(Mod101::from_residue_bits(&[true; 7]), Mod101::new(26));
+    assert_eq!(Mod101::from_residue_bits(&[]), Mod101::new(0));
+    assert!(Mod1::ZERO.residue_bits().is_empty());
+}
+
+#[test]
+fn test_choose() {
+    let binomials = Binomials::<Mod101>::new(100).unwrap();
+    for n in 0..=100 {
+        for k in 0..=101 {
+            assert_eq!(Mod101::choose(n, k), Some(binomials.choose(n, k)), "C({}, {})", n, k);
+        }
+    }
+    assert_eq!(Mod101::choose(101, 3), None);
+    assert_eq!(Mod15::choose(2, 1), Some(Mod15::new(2)));
+    assert_eq!(Mod15::choose(3, 1), None);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_shared_factorials_concurrent() {
+    let expected = Binomials::<Mod998244353>::new(3000).unwrap();
+    let handles: Vec<_> = (0..8).map(|t| std::thread::spawn(move || {
+        // each thread grows the table to a different length while the others read it
+        let limit = 1000 + 250 * t;
+        assert!(Mod998244353::ensure_factorials(limit));
+        (0..=limit).step_by(7).map(|n| (n, n / 3, Mod998244353::choose(n, n / 3).unwrap())).collect::<Vec<_>>()
+    })).collect();
+    for handle in handles {
+        for (n, k, c) in handle.join().unwrap() {
+            assert_eq!(c, expected.choose(n, k), "C({}, {})", n, k);
+        }
+    }
+    assert!(Mod1024::ensure_factorials(1));
+    assert!(!Mod1024::ensure_factorials(2));
+    assert_eq!(Mod1024::choose(1, 1), Some(Mod1024::new(1)));
+}
+
+#[test]
+fn test_add_near_half_max() {
+    fn check<M: WideModular + core::ops::Neg<Output = M>>(values: &[M]) {
+        for &a in values {
+            for &b in values {
+                let (x, y) = (a.remainder_u128(), b.remainder_u128());
+                assert_eq!((a + b).remainder_u128(), (x + y) % M::MOD_U128, "{:?} + {:?}", a, b);
+                assert_eq!((a - b).remainder_u128(), (x + M::MOD_U128 - y) % M::MOD_U128, "{:?} - {:?}", a, b);
+                assert_eq!(a + b - b, a);
+                assert_eq!((a + -a).remainder_u128(), 0);
+            }
+        }
+    }
+    let edge = |m: i64| vec![0, 1, 2, m / 2, m / 2 + 1, m - 2, m - 1];
+    check(&edge(63).into_iter().map(|x| Mod63::new(x as i8)).collect::<Vec<_>>());
+    check(&edge(16381).into_iter().map(|x| Mod16381::new(x as i16)).collect::<Vec<_>>());
+    check(&edge(1_073_741_789).into_iter().map(|x| Mod1073741789::new(x as i32)).collect::<Vec<_>>());
+    check(&edge(4_611_686_018_427_387_847).into_iter().map(Mod2p62m57::new).collect::<Vec<_>>());
+    assert_eq!(Mod16381::new(16380) + Mod16381::new(16380), Mod16381::new(16379));
+    assert_eq!(Mod2p62m57::new(-1).pow(2), Mod2p62m57::new(1));
+}
+
+#[test]
+fn test_sliding_product() {
+    fn check<M: Modular<u16>>(values: &[M], width: usize) {
+        let mut window = SlidingProduct::new();
+        for (i, &x) in values.iter().enumerate() {
+            window.push(x);
+            if window.len() > width {
+                assert_eq!(window.pop(), Some(values[i - width]));
+            }
+            let start = (i + 1).saturating_sub(width);
+            let expected = values[start..=i].iter().fold(M::one(), |acc, &y| acc * y);
+            assert_eq!(window.product(), expected, "window ending at {}", i);
+        }
+    }
+    let values: Vec<_> = (0..200_i32).map(|i| Mod101::new((i * i % 37 - 4) as i16)).collect();
+    assert!(values.contains(&Mod101::new(0)));
+    for width in 1..8 {
+        check(&values, width);
+    }
+    // non-units other than zero are recomputed as well
+    let values: Vec<_> = (0..100_i16).map(|i| Mod100::new(i * 7 + 3)).collect();
+    check(&values, 5);
+
+    let mut window = SlidingProduct::<Mod101>::default();
+    assert!(window.is_empty());
+    assert_eq!(window.product(), Mod101::new(1));
+    assert_eq!(window.pop(), None);
+}
+
+#[test]
+fn test_interpolate_poly() {
+    // 3 + 5x - 7x^2 + x^4
+    let expected = Polynomial::from_coeffs([3, 5, -7, 0, 1].iter().map(|&c| Mod101::new(c)).collect());
+    let points: Vec<_> = [0, 1, 2, 50, -3].iter().map(|&x| (Mod101::new(x), expected.eval(Mod101::new(x)))).collect();
+    assert_eq!(Mod101::interpolate_poly(&points), Some(expected.clone()));
+    // more points than needed still give the same polynomial
+    let points: Vec<_> = (10..20).map(|x| (Mod101::new(x), expected.eval(Mod101::new(x)))).collect();
+    assert_eq!(Mod101::interpolate_poly(&points), Some(expected));
+
+    let line = Mod998244353::interpolate_poly(&[(Mod998244353::new(1), Mod998244353::new(5)), (Mod998244353::new(3), Mod998244353::new(9))]).unwrap();
+    assert_eq!(line.coeffs(), &[Mod998244353::new(3), Mod998244353::new(2)]);
+    assert_eq!(Mod101::interpolate_poly(&[(Mod101::new(4), Mod101::new(7))]).unwrap().coeffs(), &[Mod101::new(7)]);
+    assert!(Mod101::interpolate_poly(&[]).unwrap().is_zero());
+    assert_eq!(Mod101::interpolate_poly(&[(Mod101::new(1), Mod101::new(2)), (Mod101::new(102), Mod101::new(3))]), None);
+    assert_eq!(Mod100::interpolate_poly(&[(Mod100::new(0), Mod100::new(2)), (Mod100::new(2), Mod100::new(3))]), None);
+}
+
+#[test]
+fn test_default_is_zero() {
+    fn check<M: Default + Zero + PartialEq + core::fmt::Debug>() {
+        assert_eq!(M::default(), M::zero());
+        assert!(M::default().is_zero());
+    }
+    check::<Mod1>();
+    check::<WidthU8>();
+    check::<Mod101>();
+    check::<Mod1024>();
+    check::<Mod998244353>();
+    check::<Mod2p89>();
+    assert_eq!(Mod101::ZERO, Mod101::default());
+}
+
+#[test]
+fn test_parse_pow_expr() {
+    assert_eq!(Mod101::parse_pow_expr("3^100"), Some(Mod101::from(3_u16).pow(100)));
+    assert_eq!(Mod101::parse_pow_expr(" -2 ^ 7 "), Some(Mod101::new(-128)));
+    assert_eq!(Mod101::parse_pow_expr("0^0"), Some(Mod101::new(1)));
+    assert_eq!(Mod998244353::parse_pow_expr("1000000000000000000000^340282366920938463463374607431768211455"),
+               Some(Mod998244353::from_u128(10).pow(21).pow_u128(u128::MAX)));
+    for bad in &["3", "3^", "^3", "3^-1", "x^2", "3^2^2", "3^1.5"] {
+        assert_eq!(Mod101::parse_pow_expr(bad), None, "{:?}", bad);
+    }
+}
+
+#[test]
+fn test_crt_reconstruct_checked() {
+    let value = 123_456_789_u128;
+    let residues = |moduli: &[u128]| moduli.iter().map(|&m| (value % m, m)).collect::<Vec<_>>();
+    let enough = residues(&[1_000_000_007, 998_244_353]);
+    assert_eq!(crt_reconstruct_checked(&enough, 1 << 40), Some(value));
+    assert_eq!(crt_reconstruct_checked(&enough, 1_000_000_007 * 998_244_353), Some(value));
+    // 101 * 103 * 107 < 2^30, so another value below the bound has the same residues
+    let too_few = residues(&[101, 103, 107]);
+    assert_eq!(crt_reconstruct_checked(&too_few, 1 << 30), None);
+    assert_eq!(crt_lift(&too_few).map(|(v, _)| v), Some(value % (101 * 103 * 107)));
+    // the residues fit the moduli but not the bound
+    assert_eq!(crt_reconstruct_checked(&enough, 1000), None);
+    assert_eq!(crt_reconstruct_checked(&[(1, 6), (1, 4)], 10), None);
+    assert_eq!(crt_reconstruct_checked(&[], 1), Some(0));
+}
+
+#[test]
+fn test_sqrt_many() {
+    let items: Vec<_> = (0..101).map(Mod101::new).collect();
+    let individual: Vec<_> = items.iter().map(|x| x.sqrt()).collect();
+    assert_eq!(Mod101::sqrt_many(&items), individual);
+    assert_eq!(individual.iter().filter(|r| r.is_some()).count(), 51);
+
+    // 998244353 - 1 = 119 * 2^23 takes the longest Tonelli-Shanks loops
+    let items: Vec<_> = (0..200).map(|i| Mod998244353::new(i * 7_654_321 - 100)).collect();
+    let roots = Mod998244353::sqrt_many(&items);
+    assert_eq!(roots, items.iter().map(|x| x.sqrt()).collect::<Vec<_>>());
+    for (x, root) in items.iter().zip(&roots) {
+        if let Some(root) = root {
+            assert_eq!(*root * *root, *x);
+        }
+    }
+
+    assert!(Mod100::sqrt_many(&[Mod100::new(4), Mod100::new(0)]).iter().all(Option::is_none));
+    assert_eq!(Mod101::sqrt_many(&[]), vec![]);
+}
+
+crate::def_modular!(SmallU128 : u128 | i128, 251 ; small_u128_lbl);
+
+// a u128 base type with a small modulus takes the widest code paths of the macro with products
+// that never need the u128 fallback
+#[test]
+fn test_small_modulus_u128() {
+    for a in 0..251_u128 {
+        let x = SmallU128::from(a);
+        if a == 0 {
+            assert_eq!(x.inv(), None);
+        } else {
+            let inv = x.inv().unwrap();
+            assert_eq!(x * inv, SmallU128::new(1), "{}", a);
+            assert_eq!(x.prime_inv(), Some(inv));
+            assert_eq!(x.coprime_inv(), Some(inv));
+            assert_eq!(x.pow_u128(250), SmallU128::new(1));
+        }
+        let mut expected = 1;
+        for n in 0..20 {
+            assert_eq!(x.pow(n).remainder(), expected, "{}^{}", a, n);
+            expected = expected * a % 251;
+        }
+        for b in (0..251_u128).step_by(7) {
+            let y = SmallU128::from(b);
+            assert_eq!((x + y).remainder(), (a + b) % 251);
+            assert_eq!((x - y).remainder(), (a + 251 - b) % 251);
+            assert_eq!((x * y).remainder(), a * b % 251);
+            if b != 0 {
+                assert_eq!(x / y * y, x);
+            }
+        }
+        assert_eq!((-x).remainder(), (251 - a) % 251);
+    }
+    assert_eq!(SmallU128::new(-1), SmallU128::from(250_u128));
+}
+
+#[test]
+fn test_const_generic_round_trip() {
+    for i in 0..101 {
+        let x = Mod101::new(i);
+        let y = x.to_const_generic::<101>();
+        assert_eq!(y.remainder(), x.remainder() as u64);
+        assert_eq!(Mod101::from_const_generic(y), x);
+        let z = Mod101::new(i * 37 - 5);
+        assert_eq!(Mod101::from_const_generic(y * z.to_const_generic() + y - z.to_const_generic()), x * z + x - z);
+        assert_eq!(Mod101::from_const_generic(-y), -x);
+    }
+    let x = Mod998244353::new(-3);
+    let y: ModU64<998_244_353> = x.to_const_generic();
+    assert_eq!(y, ModU64::new(998_244_350));
+    assert_eq!(Mod998244353::from_const_generic(y.pow(1_000_000)), x.pow(1_000_000));
+    assert_eq!(ModU64::<998_244_353>::from_modular(x).to_modular::<Mod998244353>(), x);
+    assert_eq!(Mod2p62m57::new(-1).to_const_generic::<4_611_686_018_427_387_847>().remainder(), 4_611_686_018_427_387_846);
+}
+
+#[test]
+fn test_checked_pow_signed() {
+    assert_eq!(Mod101::new(0).checked_pow_signed(-1), None);
+    assert_eq!(Mod101::new(0).checked_pow_signed(i128::MIN), None);
+    assert_eq!(Mod101::new(0).checked_pow_signed(0), Some(Mod101::new(1)));
+    assert_eq!(Mod101::new(0).checked_pow_signed(3), Some(Mod101::new(0)));
+    for i in 1..101 {
+        let x = Mod101::new(i);
+        assert_eq!(x.checked_pow_signed(-1), x.inv());
+        assert_eq!(x.checked_pow_signed(-7).unwrap() * x.pow(7), Mod101::new(1));
+        assert_eq!(x.checked_pow_signed(7), Some(x.pow(7)));
+    }
+    assert_eq!(Mod100::new(10).checked_pow_signed(-2), None);
+    assert_eq!(Mod100::new(3).checked_pow_signed(-2), Some(Mod100::new(89)));
+    assert_eq!(Mod998244353::new(2).checked_pow_signed(i128::MIN), Some(Mod998244353::new(2).inv().unwrap().pow_u128(1 << 127)));
+}
+
+#[test]
+fn test_quad_ext_trace_norm() {
+    // 101 = 5 (mod 8), so 2 is a non-residue and this is GF(101^2)
+    let sq = Mod101::new(2);
+    let elem = |re: i16, im: i16| QuadExt::new(Mod101::new(re), Mod101::new(im), sq);
+    let samples: Vec<_> = (0..30).map(|i| elem(i * 7 - 50, i * i - 3)).collect();
+    for &x in &samples {
+        for &y in &samples {
+            assert_eq!((x * y).norm(), x.norm() * y.norm());
+            assert_eq!((x + y).trace(), x.trace() + y.trace());
+        }
+        // the norm of a nonzero element of a field is nonzero
+        assert_eq!(x.norm().is_zero(), x == elem(0, 0));
+    }
+    assert_eq!(elem(3, 4).trace(), Mod101::new(6));
+    assert_eq!(elem(3, 4).norm(), Mod101::new(9 - 32));
+    assert_eq!(QuadExt::from_base(Mod101::new(5), sq).norm(), Mod101::new(25));
+    // w^2 == sq, and the norm of w is -sq
+    assert_eq!(elem(0, 1).pow(2), QuadExt::from_base(sq, sq));
+    assert_eq!(elem(0, 1).norm(), -sq);
+    // the multiplicative group of GF(101^2) has order 101^2 - 1
+    assert_eq!(elem(3, 4).pow(101 * 101 - 1), elem(1, 0));
+}
+
+#[test]
+fn test_quad_ext_frobenius() {
+    let sq = Mod101::new(2);
+    for i in 0..101 {
+        let base = QuadExt::from_base(Mod101::new(i), sq);
+        assert_eq!(base.frobenius(), base);
+        let x = QuadExt::new(Mod101::new(i), Mod101::new(i * 3 + 1), sq);
+        assert_eq!(x.frobenius(), QuadExt::new(Mod101::new(i), -Mod101::new(i * 3 + 1), sq));
+        assert_eq!(x.frobenius(), x.pow(101));
+        assert_eq!(x.frobenius().frobenius(), x);
+        // the trace and norm are the sum and product of the conjugates
+        assert_eq!(QuadExt::from_base(x.trace(), sq), x + x.frobenius());
+        assert_eq!(QuadExt::from_base(x.norm(), sq), x * x.frobenius());
+    }
+    // with a residue, w^MOD == w and the map is the identity
+    let x = QuadExt::new(Mod998244353::new(5), Mod998244353::new(7), Mod998244353::new(4));
+    assert_eq!(x.frobenius(), x);
+    assert_eq!(x.pow(998_244_353), x);
+}
+
+// every root goes through the debug assertion in Tonelli-Shanks, so this also checks that it
+// never fires for a residue
+#[test]
+fn test_sqrt_debug_validation() {
+    let mut residues = 0;
+    for i in 0..65521_u32 {
+        let x = Mod65521::from(i);
+        match x.sqrt() {
+            Some(root) => {
+                assert_eq!(root * root, x);
+                residues += 1;
+            },
+            None => assert_eq!(x.legendre(), Some(-1)),
+        }
+    }
+    assert_eq!(residues, 65521 / 2 + 1);
+    for i in 0..1000 {
+        let x = Mod998244353::new(i * 998_244 + 3);
+        assert_eq!(x.sqrt().is_some(), x.legendre() != Some(-1));
+    }
+}
+
+#[test]
+fn test_times() {
+    for i in 0..101 {
+        let x = Mod101::new(i);
+        let mut expected = Mod101::new(0);
+        for n in 0..300 {
+            assert_eq!(x.times(n), expected, "{} * {:?}", n, x);
+            expected += x;
+        }
+        assert_eq!(x.times(u128::MAX), x.mul_scalar_wide(u128::MAX));
+    }
+    let x = ModMaxU128::new(-12345);
+    for &n in &[0, 1, 2, 1 << 100, u128::MAX, u128::MAX / 3] {
+        assert_eq!(x.times(n), x.mul_scalar_wide(n));
+    }
+    assert_eq!(Mod998244353::new(3).times(998_244_353), Mod998244353::new(0));
+}
+
+#[test]
+fn test_bits_le_round_trip() {
+    for i in 0..101 {
+        let x = Mod101::new(i);
+        let bits = x.into_bits_le(7).unwrap();
+        assert_eq!(bits.len(), 7);
+        assert_eq!(bits, x.residue_bits());
+        assert_eq!(Mod101::from_bits_le(&bits), Some(x));
+        assert_eq!(Mod101::from_bits_le(&x.into_bits_le(40).unwrap()), Some(x));
+    }
+    let max = Mod101::new(100);
+    assert_eq!(max.into_bits_le(7).unwrap(), [false, false, true, false, false, true, true]);
+    assert_eq!(max.into_bits_le(6), None);
+    assert_eq!(Mod101::new(0).into_bits_le(0), Some(vec![]));
+    // 101 itself is not a representative
+    assert_eq!(Mod101::from_bits_le(&[true, false, true, false, false, true, true]), None);
+    assert_eq!(Mod101::from_bits_le(&[false; 30]), Some(Mod101::new(0)));
+
+    let max = ModMaxU128::new(-1);
+    let bits = max.into_bits_le(126).unwrap();
+    assert_eq!(ModMaxU128::from_bits_le(&bits), Some(max));
+    assert_eq!(max.into_bits_le(125), None);
+    assert_eq!(ModMaxU128::from_bits_le(&max.into_bits_le(200).unwrap()), Some(max));
+    let mut too_wide = vec![false; 129];
+    too_wide[128] = true;
+    assert_eq!(ModMaxU128::from_bits_le(&too_wide), None);
+}
+
+#[test]
+fn test_div_exact() {
+    for a in 0..101 {
+        for b in 1..101 {
+            let q = Mod101::new(a).div_exact(Mod101::new(b)).unwrap();
+            assert_eq!(q * Mod101::new(b), Mod101::new(a));
+        }
+        assert_eq!(Mod101::new(a).div_exact(Mod101::new(0)), None);
+    }
+    // 6 = 2 * 3 and 6 = 2 * 53 modulo 100, so 6 / 2 has no unique quotient
+    assert_eq!(Mod100::new(6).div_exact(Mod100::new(2)), None);
+    assert_eq!(Mod100::new(6).div_exact(Mod100::new(3)), Some(Mod100::new(2)));
+    assert_eq!(Mod100::new(7).div_exact(Mod100::new(3)), Some(Mod100::new(69)));
+}
+
+#[test]
+fn test_sigma() {
+    for n in 1..2000_u64 {
+        let expected: u64 = (1..=n).filter(|d| n % d == 0).sum();
+        assert_eq!(Mod998244353::sigma(n), Mod998244353::from(expected), "sigma({})", n);
+        assert_eq!(Mod100::sigma(n as u16), Mod100::from((expected % 100) as u16), "sigma({})", n);
+    }
+    // 2^20 * 3^12: (2^21 - 1) * (3^13 - 1) / 2
+    let n = (1 << 20) * 531_441_u64;
+    assert_eq!(Mod998244353::sigma(n), Mod998244353::from(2_097_151_u64) * Mod998244353::from(797_161_u64));
+    // p - 1 is not invertible modulo 101 for p = 101 * k + 1, e.g. 607 = 6 * 101 + 1
+    assert_eq!(Mod101::sigma(607), Mod101::new(608 - 505));
+    assert_eq!(Mod101::sigma(607 * 101), Mod101::new(2 * 102));
+}
+
+#[test]
+#[should_panic(expected = "Zero has infinitely many divisors")]
+fn test_sigma_zero() {
+    Mod101::sigma(0);
+}
+
+#[test]
+fn test_num_traits_interface() {
+    for a in 1..101 {
+        assert_eq!(crate::Inv::inv(Mod101::new(a)), Some(Mod101::new(a).inv().unwrap()));
+        assert_eq!(crate::Pow::pow(Mod101::new(a), -2_i64), crate::Inv::inv(Mod101::new(a * a)));
+        assert_eq!(crate::Pow::pow(Mod101::new(a), 3_i64), Some(Mod101::new(a).pow(3)));
+    }
+    assert_eq!(crate::Inv::inv(Mod101::new(0)), None);
+    assert_eq!(crate::Inv::inv(Mod100::new(10)), None);
+    assert_eq!(crate::Pow::pow(Mod100::new(10), -1_i64), None);
+}
+
+// Gauss-Jordan elimination written only against the Field interface
+#[cfg(feature = "field")]
+fn field_solve<F: crate::Field>(mut rows: Vec<Vec<F>>) -> Option<Vec<F>> {
+    let n = rows.len();
+    for col in 0..n {
+        let pivot = (col..n).find(|&r| !rows[r][col].is_zero())?;
+        rows.swap(col, pivot);
+        let inv = rows[col][col].invert()?;
+        for x in &mut rows[col] {
+            *x = *x * inv;
+        }
+        let pivot_row = rows[col].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            let factor = row[col];
+            if r != col && !factor.is_zero() {
+                for (x, &p) in row.iter_mut().zip(&pivot_row) {
+                    *x = *x - p * factor;
+                }
+            }
+        }
+    }
+    Some(rows.into_iter().map(|row| row[n]).collect())
+}
+
+#[cfg(feature = "field")]
+#[test]
+fn test_field_solve() {
+    // x + 2y + 3z = 14, 4x + 5y + 6z = 32, 7x + 8y + 10z = 53 has the solution (1, 2, 3)
+    let f = Mod998244353::new;
+    let rows = vec![
+        vec![f(1), f(2), f(3), f(14)],
+        vec![f(4), f(5), f(6), f(32)],
+        vec![f(7), f(8), f(10), f(53)],
+    ];
+    let solution = field_solve(rows).unwrap();
+    assert_eq!(solution, vec![Mod998244353::new(1), Mod998244353::new(2), Mod998244353::new(3)]);
+
+    // the coefficients of the second row are twice those of the first
+    let singular = vec![
+        vec![Mod101::new(1), Mod101::new(2), Mod101::new(3)],
+        vec![Mod101::new(2), Mod101::new(4), Mod101::new(5)],
+    ];
+    assert_eq!(field_solve(singular), None);
+}
+
+#[cfg(feature = "field")]
+#[test]
+fn test_field_methods() {
+    use crate::Field;
+
+    assert_eq!(<Mod101 as Field>::zero(), Mod101::new(0));
+    assert_eq!(<Mod101 as Field>::one(), Mod101::new(1));
+    assert!(Field::is_zero(&Mod101::new(101)));
+    for a in 1..101 {
+        let x = Mod101::new(a);
+        assert_eq!(x.square(), x * x);
+        assert_eq!(x.invert().map(|inv| inv * x), Some(Mod101::new(1)));
+        // Fermat's little theorem
+        assert_eq!(Field::pow(&x, 100), Mod101::new(1));
+    }
+    assert_eq!(Mod101::new(0).invert(), None);
+    assert_eq!(Mod100::new(10).invert(), None);
+    assert_eq!(Mod100::new(3).invert(), Some(Mod100::new(67)));
+}
+
+#[test]
+fn test_canonical_and_balanced() {
+    assert_eq!(Mod101::new(-3).canonical_and_balanced(), (98, -3));
+    assert_eq!(Mod101::new(50).canonical_and_balanced(), (50, 50));
+    assert_eq!(Mod101::new(51).canonical_and_balanced(), (51, -50));
+    assert_eq!(Mod100::new(50).canonical_and_balanced(), (50, 50));
+    assert_eq!(ModMaxU128::new(-1).canonical_and_balanced(), (ModMaxU128::MOD - 1, -1));
+    for i in 0..101 {
+        let x = Mod101::new(i);
+        assert_eq!(x.canonical_and_balanced(), (x.remainder(), x.signed_value()));
+    }
+}
+
+#[test]
+fn test_pow_reduced_carmichael() {
+    let expected = [(1_u64, 1_u64), (2, 1), (4, 2), (8, 2), (16, 4), (15, 4), (100, 20), (1024, 256), (561, 80), (101, 100)];
+    for &(n, lambda) in &expected {
+        assert_eq!(carmichael(n), lambda, "λ({})", n);
+    }
+    for i in 0..100 {
+        let x = Mod100::new(i);
+        for &exp in &[0, 1, 19, 20, 21, 12345, u128::MAX, (1 << 100) + 7] {
+            if i % 2 == 0 || i % 5 == 0 {
+                assert_eq!(x.pow_reduced_carmichael(exp), None);
+            } else {
+                assert_eq!(x.pow_reduced_carmichael(exp), Some(x.pow_u128(exp)), "{}^{}", i, exp);
+            }
+        }
+    }
+    for i in (1..1024).step_by(2) {
+        let x = Mod1024::new(i);
+        assert_eq!(x.pow_reduced_carmichael(u128::MAX - 3), Some(x.pow_u128(u128::MAX - 3)));
+        assert_eq!(x.pow(256), Mod1024::new(1));
+    }
+    assert_eq!(Mod1024::new(2).pow_reduced_carmichael(5), None);
+    assert_eq!(Mod998244353::new(3).pow_reduced_carmichael(998_244_352 * 5 + 2), Some(Mod998244353::new(9)));
+}
+
+crate::def_modular!(Mod3p2p30 : u64 | i64, 3_221_225_473 ; mod3p2p30_lbl);
+crate::def_modular!(Mod65537 : u32 | i32, 65537 ; mod65537_lbl);
+
+#[test]
+fn test_sqrt_algorithms() {
+    macro_rules! check {
+        ($x:expr) => {{
+            let x = $x;
+            let root = x.sqrt();
+            assert_eq!(root.is_some(), x.legendre() != Some(-1));
+            if let Some(root) = root {
+                assert_eq!(root * root, x);
+                assert!(root <= -root);
+            }
+        }};
+    }
+
+    // 3 mod 4, and 1 mod 4 with small and large 2-adicity (Tonelli-Shanks and Cipolla)
+    for i in 0..103 {
+        let x = Mod103::new(i);
+        assert_eq!(x.sqrt(), (0..103).map(Mod103::new).find(|&r| r * r == x));
+    }
+    for i in 0..101 {
+        let x = Mod101::new(i);
+        assert_eq!(x.sqrt(), (0..101).map(Mod101::new).find(|&r| r * r == x));
+    }
+    for i in (0..65537).step_by(13) {
+        check!(Mod65537::new(i));
+    }
+    for i in 0..2000 {
+        check!(Mod3p2p30::new(i * 1_610_612 + 5));
+    }
+    assert_eq!(Mod100::new(4).sqrt(), None);
+}
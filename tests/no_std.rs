@@ -0,0 +1,45 @@
+/*
+ * indent-stack
+ *
+ * Copyright (C) 2019 chankyin
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Instantiates a modular type in a `no_std` crate, which only has the `core` prelude, to check
+//! that the code generated by `def_modular!` does not depend on `std`.
+
+#![no_std]
+
+extern crate alloc;
+// the test harness itself needs std, but it is not in the prelude of this crate
+extern crate std;
+
+use generic_modular::{def_modular, Modular};
+
+def_modular!(Mod101 : u16 | i16, 101 ; mod101);
+
+#[test]
+fn test_no_std_arithmetic() {
+    let mut x = Mod101::from(7_u16);
+    x += Mod101::from(100_u16);
+    assert_eq!(x, Mod101::from(6_u16));
+    x -= Mod101::from(10_u16);
+    assert_eq!(x, Mod101::from(97_u16));
+    x *= Mod101::from(2_u16);
+    assert_eq!(x, Mod101::from(93_u16));
+    x /= Mod101::from(3_u16);
+    assert_eq!(x, Mod101::from(31_u16));
+    assert_eq!(x.pow(100), Mod101::from(1_u16));
+    assert_eq!(Mod101::inv_table().map(|table| table.len()), Some(101));
+}